@@ -1,9 +1,13 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 use tauri::Window;
-use tauri_plugin_egui::{egui, AppHandleExt};
+use tauri_plugin_egui::{egui, resize_edges, AppHandleExt, TitleBar};
+
+// whether the tool window is open, it can be closed from both windows
+static SHOW_TOOLS: AtomicBool = AtomicBool::new(false);
 
 fn main() {
   tauri::Builder::default()
@@ -16,14 +20,18 @@ fn main() {
         .inner_size(600.0, 400.0)
         .title("tauri-plugin-egui demo")
         .transparent(true)
-        .title_bar_style(tauri::TitleBarStyle::Overlay)
+        // egui draws the title bar, see `TitleBar` below
+        .decorations(false)
         .build()?;
 
       app.handle().start_egui_for_window(
         "main",
         Box::new(|ctx| {
+          egui::TopBottomPanel::top("title_bar").show(ctx, |ui| {
+            ui.add(TitleBar::new("tauri-plugin-egui demo"));
+          });
+
           egui::CentralPanel::default().show(ctx, |ui| {
-            ui.add_space(28.0);
             ui.heading("Hello from Egui!");
             ui.label("This is rendered natively with egui!");
             ui.separator();
@@ -66,6 +74,14 @@ fn main() {
 
             ui.separator();
 
+            // Viewports open in Tauri windows of their own
+            let mut show_tools = SHOW_TOOLS.load(Ordering::Relaxed);
+            if ui.checkbox(&mut show_tools, "Show tool window").changed() {
+              SHOW_TOOLS.store(show_tools, Ordering::Relaxed);
+            }
+
+            ui.separator();
+
             // Timer demonstration - shows continuous rendering
             static mut START_TIME: Option<Instant> = None;
             unsafe {
@@ -81,6 +97,28 @@ fn main() {
               }
             }
           });
+
+          if SHOW_TOOLS.load(Ordering::Relaxed) {
+            ctx.show_viewport_deferred(
+              egui::ViewportId::from_hash_of("tools"),
+              egui::ViewportBuilder::default()
+                .with_title("Tools")
+                .with_inner_size([240.0, 160.0]),
+              |ctx, _class| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                  ui.label("This is a deferred egui viewport, in a Tauri window of its own.");
+                });
+                if ctx.input(|i| i.viewport().close_requested()) {
+                  SHOW_TOOLS.store(false, Ordering::Relaxed);
+                  // the main window stops showing it
+                  ctx.request_repaint_of(egui::ViewportId::ROOT);
+                }
+              },
+            );
+          }
+
+          // last, so the resize cursors win over the ones of widgets
+          resize_edges(ctx, 4.0);
         }),
         Some(Box::new(|label| {
           println!("Window '{}' is being destroyed!", label);
@@ -0,0 +1,143 @@
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+use crate::Error;
+
+/// The clipboard egui copies to and pastes from.
+///
+/// Set a custom one with [`crate::Builder::clipboard`], the default is [`SystemClipboard`].
+pub trait Clipboard: Send {
+    /// Returns the text currently in the clipboard, if any.
+    fn get_text(&mut self) -> Option<String>;
+
+    fn set_text(&mut self, text: &str) -> Result<(), Error>;
+
+    fn set_image(&mut self, image: &egui::ColorImage) -> Result<(), Error>;
+}
+
+/// The clipboard of the operating system.
+pub struct SystemClipboard {
+    // `None` when the system clipboard is unavailable, e.g. without a display server
+    inner: Option<arboard::Clipboard>,
+}
+
+impl SystemClipboard {
+    pub fn new() -> Self {
+        Self {
+            inner: arboard::Clipboard::new().ok(),
+        }
+    }
+
+    fn inner(&mut self) -> Result<&mut arboard::Clipboard, Error> {
+        self.inner
+            .as_mut()
+            .ok_or(Error::ClipboardUnavailable)
+    }
+}
+
+impl Default for SystemClipboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.inner.as_mut()?.get_text().ok()
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), Error> {
+        self.inner()?.set_text(text)?;
+        Ok(())
+    }
+
+    fn set_image(&mut self, image: &egui::ColorImage) -> Result<(), Error> {
+        // egui stores premultiplied colors, the clipboard expects straight RGBA
+        let bytes: Vec<u8> = image
+            .pixels
+            .iter()
+            .flat_map(|color| color.to_srgba_unmultiplied())
+            .collect();
+
+        self.inner()?.set_image(arboard::ImageData {
+            width: image.width(),
+            height: image.height(),
+            bytes: Cow::Owned(bytes),
+        })?;
+        Ok(())
+    }
+}
+
+/// A clipboard that only lives in memory, e.g. to be used in tests.
+///
+/// Clones share their contents, so a test can keep one to see what egui copied
+/// and to put text there for egui to paste.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryClipboard {
+    contents: Arc<Mutex<MemoryClipboardContents>>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryClipboardContents {
+    text: Option<String>,
+    image: Option<egui::ColorImage>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The text last copied, by egui or with [`MemoryClipboard::copy_text`].
+    pub fn text(&self) -> Option<String> {
+        self.contents.lock().unwrap().text.clone()
+    }
+
+    /// The image last copied by egui.
+    pub fn image(&self) -> Option<egui::ColorImage> {
+        self.contents.lock().unwrap().image.clone()
+    }
+
+    /// Puts `text` in the clipboard, as if another app copied it.
+    pub fn copy_text(&self, text: impl Into<String>) {
+        self.contents.lock().unwrap().text = Some(text.into());
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text()
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), Error> {
+        self.copy_text(text);
+        Ok(())
+    }
+
+    fn set_image(&mut self, image: &egui::ColorImage) -> Result<(), Error> {
+        self.contents.lock().unwrap().image = Some(image.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_clipboard_clones_share_contents() {
+        let clipboard = MemoryClipboard::new();
+        // what the plugin gets with `Builder::clipboard`
+        let mut plugin_clipboard: Box<dyn Clipboard> = Box::new(clipboard.clone());
+
+        plugin_clipboard.set_text("copied by egui").unwrap();
+        assert_eq!(clipboard.text().as_deref(), Some("copied by egui"));
+
+        clipboard.copy_text("to paste");
+        assert_eq!(plugin_clipboard.get_text().as_deref(), Some("to paste"));
+
+        let image = egui::ColorImage::new([2, 1], vec![egui::Color32::RED; 2]);
+        plugin_clipboard.set_image(&image).unwrap();
+        assert_eq!(clipboard.image(), Some(image));
+    }
+}
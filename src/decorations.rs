@@ -0,0 +1,136 @@
+//! Helpers for windows without decorations, drawing their own title bar in egui.
+//!
+//! They only send [`egui::ViewportCommand`]s, which the plugin forwards to the Tauri window.
+
+use egui::{Align, Align2, CursorIcon, FontId, Layout, ResizeDirection, Sense, ViewportCommand};
+
+/// A title bar for windows without decorations.
+///
+/// Drag it to move the window, double-click it to maximize or restore the window.
+/// Shows minimize, maximize and close buttons on the right, unless turned off.
+///
+/// ```ignore
+/// egui::TopBottomPanel::top("title_bar").show(ctx, |ui| {
+///     ui.add(TitleBar::new("My App"));
+/// });
+/// ```
+pub struct TitleBar {
+    title: String,
+    height: f32,
+    buttons: bool,
+}
+
+impl TitleBar {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            height: 32.0,
+            buttons: true,
+        }
+    }
+
+    /// Sets the height of the title bar in points, `32.0` by default.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Whether to show the minimize, maximize and close buttons, `true` by default.
+    pub fn buttons(mut self, buttons: bool) -> Self {
+        self.buttons = buttons;
+        self
+    }
+}
+
+impl egui::Widget for TitleBar {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), self.height),
+            Sense::click_and_drag(),
+        );
+        let maximized = ui.input(|i| i.viewport().maximized.unwrap_or(false));
+
+        ui.painter().text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            &self.title,
+            FontId::proportional(self.height * 0.45),
+            ui.visuals().text_color(),
+        );
+
+        if response.double_clicked() {
+            ui.ctx().send_viewport_cmd(ViewportCommand::Maximized(!maximized));
+        } else if response.drag_started_by(egui::PointerButton::Primary) {
+            ui.ctx().send_viewport_cmd(ViewportCommand::StartDrag);
+        }
+
+        if self.buttons {
+            // added after the bar, so the buttons get the clicks
+            let layout = Layout::right_to_left(Align::Center);
+            ui.scope_builder(egui::UiBuilder::new().max_rect(rect).layout(layout), |ui| {
+                ui.add_space(8.0);
+                if ui.button("❌").on_hover_text("Close").clicked() {
+                    ui.ctx().send_viewport_cmd(ViewportCommand::Close);
+                }
+                let (icon, tooltip) = if maximized {
+                    ("🗗", "Restore")
+                } else {
+                    ("🗖", "Maximize")
+                };
+                if ui.button(icon).on_hover_text(tooltip).clicked() {
+                    ui.ctx().send_viewport_cmd(ViewportCommand::Maximized(!maximized));
+                }
+                if ui.button("🗕").on_hover_text("Minimize").clicked() {
+                    ui.ctx().send_viewport_cmd(ViewportCommand::Minimized(true));
+                }
+            });
+        }
+
+        response
+    }
+}
+
+/// Lets the user resize a window without decorations by dragging its edges and corners.
+///
+/// Shows the matching resize cursor within `border` points of the window's edges.
+/// Call it after the rest of the UI, so its cursor wins over the ones of widgets.
+/// Does nothing while the window is maximized or fullscreen.
+pub fn resize_edges(ctx: &egui::Context, border: f32) {
+    let (hover_pos, pressed, fixed) = ctx.input(|i| {
+        let viewport = i.viewport();
+        (
+            i.pointer.hover_pos(),
+            i.pointer.primary_pressed(),
+            viewport.maximized.unwrap_or(false) || viewport.fullscreen.unwrap_or(false),
+        )
+    });
+    let Some(pos) = hover_pos else {
+        return;
+    };
+    if fixed {
+        return;
+    }
+
+    let rect = ctx.viewport_rect();
+    let north = pos.y <= rect.top() + border;
+    let south = pos.y >= rect.bottom() - border;
+    let west = pos.x <= rect.left() + border;
+    let east = pos.x >= rect.right() - border;
+
+    let (direction, cursor) = match (north, south, west, east) {
+        (true, _, true, _) => (ResizeDirection::NorthWest, CursorIcon::ResizeNorthWest),
+        (true, _, _, true) => (ResizeDirection::NorthEast, CursorIcon::ResizeNorthEast),
+        (_, true, true, _) => (ResizeDirection::SouthWest, CursorIcon::ResizeSouthWest),
+        (_, true, _, true) => (ResizeDirection::SouthEast, CursorIcon::ResizeSouthEast),
+        (true, ..) => (ResizeDirection::North, CursorIcon::ResizeNorth),
+        (_, true, ..) => (ResizeDirection::South, CursorIcon::ResizeSouth),
+        (_, _, true, _) => (ResizeDirection::West, CursorIcon::ResizeWest),
+        (.., true) => (ResizeDirection::East, CursorIcon::ResizeEast),
+        _ => return,
+    };
+
+    ctx.set_cursor_icon(cursor);
+    if pressed {
+        ctx.send_viewport_cmd(ViewportCommand::BeginResize(direction));
+    }
+}
@@ -0,0 +1,57 @@
+use egui_wgpu::wgpu;
+use serde::{Serialize, Serializer};
+
+/// The errors of the egui plugin.
+///
+/// Serializes to its message, so it can be returned from Tauri commands.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("no window found with the label `{0}`")]
+    WindowNotFound(String),
+    #[error("the egui plugin is not initialized, register it with `Builder::build` first")]
+    PluginNotInitialized,
+    #[error("egui is already started for the window `{0}`")]
+    AlreadyAttached(String),
+    #[error("egui is not started for the window `{0}`")]
+    NotAttached(String),
+    #[error("failed to create a GPU surface for the window: {0}")]
+    SurfaceCreation(#[from] wgpu::CreateSurfaceError),
+    #[error("the GPU adapter cannot present to the window")]
+    SurfaceUnsupported,
+    #[error("no suitable GPU adapter found: {0}")]
+    AdapterUnavailable(#[from] wgpu::RequestAdapterError),
+    #[error("failed to request a GPU device: {0}")]
+    DeviceRequest(#[from] wgpu::RequestDeviceError),
+    #[error("failed to render a frame: {0}")]
+    Surface(#[from] wgpu::SurfaceError),
+    #[error("failed to wait for the GPU: {0}")]
+    Poll(#[from] wgpu::PollError),
+    #[error("failed to read the rendered frame back: {0}")]
+    BufferAsync(#[from] wgpu::BufferAsyncError),
+    #[error("the scroll speed must be a finite number above zero, got {0}")]
+    InvalidScrollSpeed(f32),
+    #[error("the system clipboard is not available")]
+    ClipboardUnavailable,
+    #[error(transparent)]
+    Clipboard(#[from] arboard::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+    /// Errors of custom [`crate::Clipboard`] and [`crate::UrlOpener`] implementations.
+    #[error(transparent)]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<Error> for tauri::Error {
+    fn from(error: Error) -> Self {
+        tauri::Error::Anyhow(error.into())
+    }
+}
@@ -0,0 +1,324 @@
+use tauri::PhysicalSize;
+use tauri_runtime_wry::tao::dpi::PhysicalPosition;
+use tauri_runtime_wry::tao::event::{
+    ElementState, MouseButton, MouseScrollDelta, Touch, WindowEvent as TaoWindowEvent,
+};
+
+use crate::touch::{translate_touch_phase, TouchPointer};
+
+/// The input of an egui window, gathered from tao events until the next pass takes it.
+pub(crate) struct WindowInput {
+    // egui zooms on top of the scale factor of the window
+    context: egui::Context,
+    pub(crate) egui_input: egui::RawInput,
+    pub(crate) pointer_pos: Option<egui::Pos2>,
+    touch_pointer: TouchPointer,
+    pub(crate) scale_factor: f32,
+    // multiplies the mouse wheel deltas
+    pub(crate) scroll_speed: f32,
+    pub(crate) modifiers: egui::Modifiers,
+    pub(crate) focused: bool,
+}
+
+impl WindowInput {
+    pub(crate) fn new(context: egui::Context, scale_factor: f32, focused: bool) -> Self {
+        Self {
+            context,
+            egui_input: egui::RawInput::default(),
+            pointer_pos: None,
+            touch_pointer: TouchPointer::default(),
+            scale_factor,
+            scroll_speed: 1.0,
+            modifiers: egui::Modifiers::NONE,
+            focused,
+        }
+    }
+
+    /// Physical pixels per egui point, which is what egui lays out with.
+    pub(crate) fn pixels_per_point(&self) -> f32 {
+        self.context.zoom_factor() * self.scale_factor
+    }
+
+    /// The area of a window of `size`, in points.
+    pub(crate) fn screen_rect(&self, size: PhysicalSize<u32>) -> egui::Rect {
+        let pixels_per_point = self.pixels_per_point();
+        egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::Vec2::new(
+                size.width as f32 / pixels_per_point,
+                size.height as f32 / pixels_per_point,
+            ),
+        )
+    }
+
+    fn to_points(&self, position: &PhysicalPosition<f64>) -> egui::Pos2 {
+        let pixels_per_point = self.pixels_per_point();
+        egui::Pos2::new(
+            position.x as f32 / pixels_per_point,
+            position.y as f32 / pixels_per_point,
+        )
+    }
+
+    /// Translates the pointer, wheel, touch, focus and modifier events of the window.
+    ///
+    /// Returns whether egui got an event out of it.
+    pub(crate) fn handle_event(&mut self, event: &TaoWindowEvent<'_>) -> bool {
+        match event {
+            TaoWindowEvent::CursorMoved { position, .. } => {
+                let pos = self.to_points(position);
+                self.pointer_pos = Some(pos);
+                self.egui_input.events.push(egui::Event::PointerMoved(pos));
+                true
+            }
+            TaoWindowEvent::CursorLeft { .. } => {
+                // otherwise hover highlights stick once the pointer left the window
+                self.pointer_pos = None;
+                self.egui_input.events.push(egui::Event::PointerGone);
+                true
+            }
+            TaoWindowEvent::Focused(focused) => {
+                self.focused = *focused;
+                self.egui_input.events.push(egui::Event::WindowFocused(*focused));
+                if !focused {
+                    // modifier releases happening in another window are never reported
+                    self.modifiers = egui::Modifiers::NONE;
+                    self.egui_input.modifiers = self.modifiers;
+                }
+                true
+            }
+            TaoWindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = egui::Modifiers {
+                    alt: modifiers.alt_key(),
+                    ctrl: modifiers.control_key(),
+                    shift: modifiers.shift_key(),
+                    #[cfg(target_os = "macos")]
+                    mac_cmd: modifiers.super_key(),
+                    #[cfg(target_os = "macos")]
+                    command: modifiers.super_key(),
+                    #[cfg(not(target_os = "macos"))]
+                    mac_cmd: false,
+                    #[cfg(not(target_os = "macos"))]
+                    command: modifiers.control_key(),
+                };
+                self.egui_input.modifiers = self.modifiers;
+                true
+            }
+            TaoWindowEvent::MouseInput { state, button, .. } => {
+                let pressed = *state == ElementState::Pressed;
+                let button = match button {
+                    MouseButton::Left => egui::PointerButton::Primary,
+                    MouseButton::Right => egui::PointerButton::Secondary,
+                    MouseButton::Middle => egui::PointerButton::Middle,
+                    // the back and forward buttons, macOS reports them as `Middle`
+                    #[cfg(target_os = "windows")]
+                    MouseButton::Other(1) => egui::PointerButton::Extra1,
+                    #[cfg(target_os = "windows")]
+                    MouseButton::Other(2) => egui::PointerButton::Extra2,
+                    #[cfg(target_os = "linux")]
+                    MouseButton::Other(8) => egui::PointerButton::Extra1,
+                    #[cfg(target_os = "linux")]
+                    MouseButton::Other(9) => egui::PointerButton::Extra2,
+                    _ => return false,
+                };
+
+                // Use current pointer position, or default to (0,0) if not set
+                let pos = self.pointer_pos.unwrap_or(egui::Pos2::ZERO);
+
+                self.egui_input.events.push(egui::Event::PointerButton {
+                    pos,
+                    button,
+                    pressed,
+                    modifiers: self.modifiers,
+                });
+                true
+            }
+            TaoWindowEvent::MouseWheel { delta, .. } => {
+                // egui turns lines into points with its own scroll settings
+                let (unit, delta) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        (egui::MouseWheelUnit::Line, egui::Vec2::new(*x, *y))
+                    }
+                    MouseScrollDelta::PixelDelta(pos) => {
+                        (egui::MouseWheelUnit::Point, self.to_points(pos).to_vec2())
+                    }
+                    _ => return false,
+                };
+                self.egui_input.events.push(egui::Event::MouseWheel {
+                    unit,
+                    delta: delta * self.scroll_speed,
+                    modifiers: self.modifiers,
+                });
+                true
+            }
+            TaoWindowEvent::Touch(touch) => {
+                self.handle_touch_event(touch);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Translates a touch, egui derives pinch zoom and rotation from multiple touches itself.
+    fn handle_touch_event(&mut self, touch: &Touch) {
+        let pos = self.to_points(&touch.location);
+        let phase = translate_touch_phase(touch.phase);
+
+        self.egui_input.events.push(egui::Event::Touch {
+            device_id: egui::TouchDeviceId(egui::util::hash(touch.device_id)),
+            id: egui::TouchId(touch.id),
+            phase,
+            pos,
+            force: touch.force.map(|force| force.normalized() as f32),
+        });
+
+        // the first touch also drives the pointer
+        let pointer_events = self.touch_pointer.translate(
+            touch.id,
+            phase,
+            pos,
+            self.modifiers,
+            &mut self.pointer_pos,
+        );
+        self.egui_input.events.extend(pointer_events);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tauri_runtime_wry::tao::event::{DeviceId, TouchPhase};
+
+    fn device_id() -> DeviceId {
+        // only compared and hashed, never handed to tao
+        unsafe { DeviceId::dummy() }
+    }
+
+    #[allow(deprecated)]
+    fn cursor_moved(x: f64, y: f64) -> TaoWindowEvent<'static> {
+        TaoWindowEvent::CursorMoved {
+            device_id: device_id(),
+            position: PhysicalPosition::new(x, y),
+            modifiers: Default::default(),
+        }
+    }
+
+    fn touch(id: u64, phase: TouchPhase, x: f64, y: f64) -> TaoWindowEvent<'static> {
+        TaoWindowEvent::Touch(Touch {
+            device_id: device_id(),
+            phase,
+            location: PhysicalPosition::new(x, y),
+            force: None,
+            id,
+        })
+    }
+
+    /// Runs a pass of the context with the input gathered so far.
+    fn run_pass(input: &mut WindowInput) {
+        let raw_input = std::mem::take(&mut input.egui_input);
+        let _ = input.context.run(raw_input, |_| {});
+    }
+
+    fn press(pos: egui::Pos2, pressed: bool) -> egui::Event {
+        egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: egui::Modifiers::NONE,
+        }
+    }
+
+    /// The events other than the touches themselves.
+    fn pointer_events(input: &mut WindowInput) -> Vec<egui::Event> {
+        std::mem::take(&mut input.egui_input.events)
+            .into_iter()
+            .filter(|event| !matches!(event, egui::Event::Touch { .. }))
+            .collect()
+    }
+
+    #[test]
+    fn pointer_position_follows_the_zoom() {
+        let context = egui::Context::default();
+        let mut input = WindowInput::new(context.clone(), 2.0, true);
+
+        input.handle_event(&cursor_moved(300.0, 150.0));
+        assert_eq!(input.pointer_pos, Some(egui::pos2(150.0, 75.0)));
+
+        // Ctrl/Cmd + Plus, which egui zooms in with, from the next pass on
+        input.egui_input.events.push(egui::Event::Key {
+            key: egui::Key::Plus,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::COMMAND,
+        });
+        run_pass(&mut input);
+        run_pass(&mut input);
+        let zoom_factor = context.zoom_factor();
+        assert!(zoom_factor > 1.0, "not zoomed in: {zoom_factor}");
+        assert_eq!(input.pixels_per_point(), zoom_factor * 2.0);
+
+        input.handle_event(&cursor_moved(300.0, 150.0));
+        let pos = input.pointer_pos.unwrap();
+        assert!((pos.x - 150.0 / zoom_factor).abs() < 1e-3, "{pos:?}");
+        assert!((pos.y - 75.0 / zoom_factor).abs() < 1e-3, "{pos:?}");
+
+        let screen_rect = input.screen_rect(PhysicalSize::new(800, 600));
+        assert!((screen_rect.width() - 400.0 / zoom_factor).abs() < 1e-3, "{screen_rect:?}");
+        assert!((screen_rect.height() - 300.0 / zoom_factor).abs() < 1e-3, "{screen_rect:?}");
+    }
+
+    #[test]
+    fn touch_drives_the_pointer() {
+        let mut input = WindowInput::new(egui::Context::default(), 2.0, true);
+        let (a, b) = (egui::pos2(10.0, 20.0), egui::pos2(15.0, 25.0));
+
+        assert!(input.handle_event(&touch(1, TouchPhase::Started, 20.0, 40.0)));
+        let events = std::mem::take(&mut input.egui_input.events);
+        assert!(matches!(
+            events[0],
+            egui::Event::Touch { id: egui::TouchId(1), phase: egui::TouchPhase::Start, pos, .. } if pos == a
+        ));
+        assert_eq!(events[1..], [egui::Event::PointerMoved(a), press(a, true)]);
+        assert_eq!(input.pointer_pos, Some(a));
+
+        // a second finger only reaches egui as a touch
+        input.handle_event(&touch(2, TouchPhase::Started, 100.0, 100.0));
+        input.handle_event(&touch(2, TouchPhase::Moved, 110.0, 100.0));
+        assert_eq!(pointer_events(&mut input), []);
+        assert_eq!(input.pointer_pos, Some(a));
+
+        input.handle_event(&touch(1, TouchPhase::Moved, 30.0, 50.0));
+        assert_eq!(pointer_events(&mut input), [egui::Event::PointerMoved(b)]);
+        assert_eq!(input.pointer_pos, Some(b));
+
+        input.handle_event(&touch(1, TouchPhase::Ended, 30.0, 50.0));
+        assert_eq!(pointer_events(&mut input), [press(b, false), egui::Event::PointerGone]);
+        assert_eq!(input.pointer_pos, None);
+
+        input.handle_event(&touch(2, TouchPhase::Cancelled, 110.0, 100.0));
+        assert_eq!(pointer_events(&mut input), []);
+    }
+
+    #[test]
+    fn cancelled_touch_releases_the_pointer() {
+        let mut input = WindowInput::new(egui::Context::default(), 1.0, true);
+        let a = egui::pos2(10.0, 20.0);
+
+        input.handle_event(&touch(7, TouchPhase::Started, 10.0, 20.0));
+        assert_eq!(pointer_events(&mut input), [egui::Event::PointerMoved(a), press(a, true)]);
+
+        input.handle_event(&touch(7, TouchPhase::Cancelled, 10.0, 20.0));
+        let events = std::mem::take(&mut input.egui_input.events);
+        assert!(matches!(
+            events[0],
+            egui::Event::Touch { phase: egui::TouchPhase::Cancel, .. }
+        ));
+        assert_eq!(events[1..], [egui::Event::PointerGone]);
+        assert_eq!(input.pointer_pos, None);
+
+        // the next touch drives the pointer again
+        input.handle_event(&touch(8, TouchPhase::Started, 10.0, 20.0));
+        assert_eq!(pointer_events(&mut input), [egui::Event::PointerMoved(a), press(a, true)]);
+    }
+}
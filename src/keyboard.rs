@@ -0,0 +1,384 @@
+use tauri_runtime_wry::tao::keyboard::{Key, KeyCode};
+
+/// Translates a key press or release, with the logical key falling back to the physical one.
+///
+/// The physical key lets egui apps bind shortcuts by position, e.g. WASD on any layout.
+pub(crate) fn translate_key_event(
+    logical_key: &Key,
+    physical_key: &KeyCode,
+    pressed: bool,
+    repeat: bool,
+    modifiers: egui::Modifiers,
+) -> Option<egui::Event> {
+    let physical_key = translate_physical_key(physical_key);
+    let key = translate_logical_key(logical_key).or(physical_key)?;
+    Some(egui::Event::Key {
+        key,
+        physical_key,
+        pressed,
+        repeat,
+        modifiers,
+    })
+}
+
+/// Translates the key as produced by the keyboard layout, e.g. for shortcuts.
+pub(crate) fn translate_logical_key(key: &Key) -> Option<egui::Key> {
+    Some(match key {
+        Key::Character(ch) => return translate_character(ch),
+
+        Key::ArrowDown => egui::Key::ArrowDown,
+        Key::ArrowLeft => egui::Key::ArrowLeft,
+        Key::ArrowRight => egui::Key::ArrowRight,
+        Key::ArrowUp => egui::Key::ArrowUp,
+
+        Key::Escape => egui::Key::Escape,
+        Key::Tab => egui::Key::Tab,
+        Key::Backspace => egui::Key::Backspace,
+        Key::Enter => egui::Key::Enter,
+        Key::Space => egui::Key::Space,
+
+        Key::Insert | Key::Help => egui::Key::Insert,
+        Key::Delete => egui::Key::Delete,
+        Key::Home => egui::Key::Home,
+        Key::End => egui::Key::End,
+        Key::PageUp => egui::Key::PageUp,
+        Key::PageDown => egui::Key::PageDown,
+
+        Key::Copy => egui::Key::Copy,
+        Key::Cut => egui::Key::Cut,
+        Key::Paste => egui::Key::Paste,
+
+        Key::BrowserBack => egui::Key::BrowserBack,
+
+        Key::F1 => egui::Key::F1,
+        Key::F2 => egui::Key::F2,
+        Key::F3 => egui::Key::F3,
+        Key::F4 => egui::Key::F4,
+        Key::F5 => egui::Key::F5,
+        Key::F6 => egui::Key::F6,
+        Key::F7 => egui::Key::F7,
+        Key::F8 => egui::Key::F8,
+        Key::F9 => egui::Key::F9,
+        Key::F10 => egui::Key::F10,
+        Key::F11 => egui::Key::F11,
+        Key::F12 => egui::Key::F12,
+        Key::F13 => egui::Key::F13,
+        Key::F14 => egui::Key::F14,
+        Key::F15 => egui::Key::F15,
+        Key::F16 => egui::Key::F16,
+        Key::F17 => egui::Key::F17,
+        Key::F18 => egui::Key::F18,
+        Key::F19 => egui::Key::F19,
+        Key::F20 => egui::Key::F20,
+        Key::F21 => egui::Key::F21,
+        Key::F22 => egui::Key::F22,
+        Key::F23 => egui::Key::F23,
+        Key::F24 => egui::Key::F24,
+        Key::F25 => egui::Key::F25,
+        Key::F26 => egui::Key::F26,
+        Key::F27 => egui::Key::F27,
+        Key::F28 => egui::Key::F28,
+        Key::F29 => egui::Key::F29,
+        Key::F30 => egui::Key::F30,
+        Key::F31 => egui::Key::F31,
+        Key::F32 => egui::Key::F32,
+        Key::F33 => egui::Key::F33,
+        Key::F34 => egui::Key::F34,
+        Key::F35 => egui::Key::F35,
+
+        _ => return None,
+    })
+}
+
+/// Translates the character of a key, e.g. `a`, `+` or `[`.
+fn translate_character(ch: &str) -> Option<egui::Key> {
+    match ch {
+        // some platforms report these control characters instead of the named keys
+        "\t" => Some(egui::Key::Tab),
+        "\n" | "\r" => Some(egui::Key::Enter),
+        "\x08" => Some(egui::Key::Backspace),
+        "\x7f" => Some(egui::Key::Delete),
+        "\x1b" => Some(egui::Key::Escape),
+        // letters, digits and all the punctuation egui has a key for
+        _ => egui::Key::from_name(ch),
+    }
+}
+
+/// Translates the position of a key on a US keyboard, regardless of the layout.
+pub(crate) fn translate_physical_key(key: &KeyCode) -> Option<egui::Key> {
+    Some(match key {
+        KeyCode::ArrowDown => egui::Key::ArrowDown,
+        KeyCode::ArrowLeft => egui::Key::ArrowLeft,
+        KeyCode::ArrowRight => egui::Key::ArrowRight,
+        KeyCode::ArrowUp => egui::Key::ArrowUp,
+
+        KeyCode::Escape => egui::Key::Escape,
+        KeyCode::Tab => egui::Key::Tab,
+        KeyCode::Backspace | KeyCode::NumpadBackspace => egui::Key::Backspace,
+        KeyCode::Enter | KeyCode::NumpadEnter => egui::Key::Enter,
+        KeyCode::Space => egui::Key::Space,
+
+        KeyCode::Insert | KeyCode::Help => egui::Key::Insert,
+        KeyCode::Delete => egui::Key::Delete,
+        KeyCode::Home => egui::Key::Home,
+        KeyCode::End => egui::Key::End,
+        KeyCode::PageUp => egui::Key::PageUp,
+        KeyCode::PageDown => egui::Key::PageDown,
+
+        KeyCode::Copy => egui::Key::Copy,
+        KeyCode::Cut => egui::Key::Cut,
+        KeyCode::Paste => egui::Key::Paste,
+
+        KeyCode::Comma | KeyCode::NumpadComma => egui::Key::Comma,
+        KeyCode::Minus | KeyCode::NumpadSubtract => egui::Key::Minus,
+        KeyCode::Period | KeyCode::NumpadDecimal => egui::Key::Period,
+        KeyCode::Plus | KeyCode::NumpadAdd => egui::Key::Plus,
+        KeyCode::Equal | KeyCode::NumpadEqual => egui::Key::Equals,
+        KeyCode::Semicolon => egui::Key::Semicolon,
+        KeyCode::Backslash | KeyCode::IntlBackslash => egui::Key::Backslash,
+        KeyCode::Slash | KeyCode::NumpadDivide => egui::Key::Slash,
+        KeyCode::BracketLeft => egui::Key::OpenBracket,
+        KeyCode::BracketRight => egui::Key::CloseBracket,
+        KeyCode::Backquote => egui::Key::Backtick,
+        KeyCode::Quote => egui::Key::Quote,
+
+        KeyCode::Digit0 | KeyCode::Numpad0 => egui::Key::Num0,
+        KeyCode::Digit1 | KeyCode::Numpad1 => egui::Key::Num1,
+        KeyCode::Digit2 | KeyCode::Numpad2 => egui::Key::Num2,
+        KeyCode::Digit3 | KeyCode::Numpad3 => egui::Key::Num3,
+        KeyCode::Digit4 | KeyCode::Numpad4 => egui::Key::Num4,
+        KeyCode::Digit5 | KeyCode::Numpad5 => egui::Key::Num5,
+        KeyCode::Digit6 | KeyCode::Numpad6 => egui::Key::Num6,
+        KeyCode::Digit7 | KeyCode::Numpad7 => egui::Key::Num7,
+        KeyCode::Digit8 | KeyCode::Numpad8 => egui::Key::Num8,
+        KeyCode::Digit9 | KeyCode::Numpad9 => egui::Key::Num9,
+
+        KeyCode::KeyA => egui::Key::A,
+        KeyCode::KeyB => egui::Key::B,
+        KeyCode::KeyC => egui::Key::C,
+        KeyCode::KeyD => egui::Key::D,
+        KeyCode::KeyE => egui::Key::E,
+        KeyCode::KeyF => egui::Key::F,
+        KeyCode::KeyG => egui::Key::G,
+        KeyCode::KeyH => egui::Key::H,
+        KeyCode::KeyI => egui::Key::I,
+        KeyCode::KeyJ => egui::Key::J,
+        KeyCode::KeyK => egui::Key::K,
+        KeyCode::KeyL => egui::Key::L,
+        KeyCode::KeyM => egui::Key::M,
+        KeyCode::KeyN => egui::Key::N,
+        KeyCode::KeyO => egui::Key::O,
+        KeyCode::KeyP => egui::Key::P,
+        KeyCode::KeyQ => egui::Key::Q,
+        KeyCode::KeyR => egui::Key::R,
+        KeyCode::KeyS => egui::Key::S,
+        KeyCode::KeyT => egui::Key::T,
+        KeyCode::KeyU => egui::Key::U,
+        KeyCode::KeyV => egui::Key::V,
+        KeyCode::KeyW => egui::Key::W,
+        KeyCode::KeyX => egui::Key::X,
+        KeyCode::KeyY => egui::Key::Y,
+        KeyCode::KeyZ => egui::Key::Z,
+
+        KeyCode::BrowserBack => egui::Key::BrowserBack,
+
+        KeyCode::F1 => egui::Key::F1,
+        KeyCode::F2 => egui::Key::F2,
+        KeyCode::F3 => egui::Key::F3,
+        KeyCode::F4 => egui::Key::F4,
+        KeyCode::F5 => egui::Key::F5,
+        KeyCode::F6 => egui::Key::F6,
+        KeyCode::F7 => egui::Key::F7,
+        KeyCode::F8 => egui::Key::F8,
+        KeyCode::F9 => egui::Key::F9,
+        KeyCode::F10 => egui::Key::F10,
+        KeyCode::F11 => egui::Key::F11,
+        KeyCode::F12 => egui::Key::F12,
+        KeyCode::F13 => egui::Key::F13,
+        KeyCode::F14 => egui::Key::F14,
+        KeyCode::F15 => egui::Key::F15,
+        KeyCode::F16 => egui::Key::F16,
+        KeyCode::F17 => egui::Key::F17,
+        KeyCode::F18 => egui::Key::F18,
+        KeyCode::F19 => egui::Key::F19,
+        KeyCode::F20 => egui::Key::F20,
+        KeyCode::F21 => egui::Key::F21,
+        KeyCode::F22 => egui::Key::F22,
+        KeyCode::F23 => egui::Key::F23,
+        KeyCode::F24 => egui::Key::F24,
+        KeyCode::F25 => egui::Key::F25,
+        KeyCode::F26 => egui::Key::F26,
+        KeyCode::F27 => egui::Key::F27,
+        KeyCode::F28 => egui::Key::F28,
+        KeyCode::F29 => egui::Key::F29,
+        KeyCode::F30 => egui::Key::F30,
+        KeyCode::F31 => egui::Key::F31,
+        KeyCode::F32 => egui::Key::F32,
+        KeyCode::F33 => egui::Key::F33,
+        KeyCode::F34 => egui::Key::F34,
+        KeyCode::F35 => egui::Key::F35,
+
+        // modifiers, media keys and the like have no egui key
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logical_keys() {
+        let cases = [
+            // punctuation, as typed
+            (Key::Character(","), Some(egui::Key::Comma)),
+            (Key::Character("-"), Some(egui::Key::Minus)),
+            (Key::Character("."), Some(egui::Key::Period)),
+            (Key::Character("+"), Some(egui::Key::Plus)),
+            (Key::Character("="), Some(egui::Key::Equals)),
+            (Key::Character(";"), Some(egui::Key::Semicolon)),
+            (Key::Character(":"), Some(egui::Key::Colon)),
+            (Key::Character("\\"), Some(egui::Key::Backslash)),
+            (Key::Character("/"), Some(egui::Key::Slash)),
+            (Key::Character("["), Some(egui::Key::OpenBracket)),
+            (Key::Character("]"), Some(egui::Key::CloseBracket)),
+            (Key::Character("`"), Some(egui::Key::Backtick)),
+            (Key::Character("'"), Some(egui::Key::Quote)),
+            (Key::Character("?"), Some(egui::Key::Questionmark)),
+            (Key::Character("|"), Some(egui::Key::Pipe)),
+            // letters and digits, in either case
+            (Key::Character("a"), Some(egui::Key::A)),
+            (Key::Character("A"), Some(egui::Key::A)),
+            (Key::Character("7"), Some(egui::Key::Num7)),
+            // control characters some platforms report instead of the named keys
+            (Key::Character("\t"), Some(egui::Key::Tab)),
+            (Key::Character("\n"), Some(egui::Key::Enter)),
+            (Key::Character("\r"), Some(egui::Key::Enter)),
+            (Key::Character("\x08"), Some(egui::Key::Backspace)),
+            (Key::Character("\x7f"), Some(egui::Key::Delete)),
+            (Key::Character("\x1b"), Some(egui::Key::Escape)),
+            // characters egui has no key for
+            (Key::Character("é"), None),
+            (Key::Character("с"), None),
+            // named keys
+            (Key::Copy, Some(egui::Key::Copy)),
+            (Key::Cut, Some(egui::Key::Cut)),
+            (Key::Paste, Some(egui::Key::Paste)),
+            (Key::Help, Some(egui::Key::Insert)),
+            (Key::BrowserBack, Some(egui::Key::BrowserBack)),
+            (Key::F1, Some(egui::Key::F1)),
+            (Key::F13, Some(egui::Key::F13)),
+            (Key::F24, Some(egui::Key::F24)),
+            (Key::F35, Some(egui::Key::F35)),
+            (Key::Shift, None),
+            (Key::AudioVolumeUp, None),
+        ];
+        for (key, expected) in cases {
+            assert_eq!(translate_logical_key(&key), expected, "{key:?}");
+        }
+    }
+
+    fn key_event(logical_key: Key, physical_key: KeyCode) -> Option<egui::Event> {
+        translate_key_event(&logical_key, &physical_key, true, false, egui::Modifiers::NONE)
+    }
+
+    fn key_and_physical_key(event: Option<egui::Event>) -> Option<(egui::Key, Option<egui::Key>)> {
+        match event? {
+            egui::Event::Key { key, physical_key, .. } => Some((key, physical_key)),
+            event => panic!("not a key event: {event:?}"),
+        }
+    }
+
+    #[test]
+    fn key_events() {
+        let cases = [
+            // the same layout
+            (Key::Character("a"), KeyCode::KeyA, Some((egui::Key::A, Some(egui::Key::A)))),
+            // AZERTY, the key is what's typed, the physical key where it is on a US keyboard
+            (Key::Character("a"), KeyCode::KeyQ, Some((egui::Key::A, Some(egui::Key::Q)))),
+            (Key::Character("z"), KeyCode::KeyW, Some((egui::Key::Z, Some(egui::Key::W)))),
+            (Key::Character(";"), KeyCode::Comma, Some((egui::Key::Semicolon, Some(egui::Key::Comma)))),
+            // Cyrillic, egui has no key for the character, so the physical key stands in
+            (Key::Character("с"), KeyCode::KeyC, Some((egui::Key::C, Some(egui::Key::C)))),
+            (Key::Character("ф"), KeyCode::KeyA, Some((egui::Key::A, Some(egui::Key::A)))),
+            // neither has an egui key
+            (Key::Character("é"), KeyCode::Digit2, Some((egui::Key::Num2, Some(egui::Key::Num2)))),
+            (Key::Character("ß"), KeyCode::IntlRo, None),
+            (Key::Shift, KeyCode::ShiftLeft, None),
+            // named keys
+            (Key::Enter, KeyCode::NumpadEnter, Some((egui::Key::Enter, Some(egui::Key::Enter)))),
+            (Key::Copy, KeyCode::Copy, Some((egui::Key::Copy, Some(egui::Key::Copy)))),
+        ];
+        for (logical_key, physical_key, expected) in cases {
+            let description = format!("{logical_key:?} at {physical_key:?}");
+            let event = key_event(logical_key, physical_key);
+            assert_eq!(key_and_physical_key(event), expected, "{description}");
+        }
+    }
+
+    #[test]
+    fn key_event_state() {
+        let modifiers = egui::Modifiers::CTRL;
+        let event = translate_key_event(&Key::Character("a"), &KeyCode::KeyQ, false, true, modifiers);
+        assert_eq!(
+            event,
+            Some(egui::Event::Key {
+                key: egui::Key::A,
+                physical_key: Some(egui::Key::Q),
+                pressed: false,
+                repeat: true,
+                modifiers,
+            })
+        );
+    }
+
+    #[test]
+    fn physical_keys() {
+        let cases = [
+            // punctuation
+            (KeyCode::Comma, Some(egui::Key::Comma)),
+            (KeyCode::Minus, Some(egui::Key::Minus)),
+            (KeyCode::Period, Some(egui::Key::Period)),
+            (KeyCode::Equal, Some(egui::Key::Equals)),
+            (KeyCode::Semicolon, Some(egui::Key::Semicolon)),
+            (KeyCode::Backslash, Some(egui::Key::Backslash)),
+            (KeyCode::IntlBackslash, Some(egui::Key::Backslash)),
+            (KeyCode::Slash, Some(egui::Key::Slash)),
+            (KeyCode::BracketLeft, Some(egui::Key::OpenBracket)),
+            (KeyCode::BracketRight, Some(egui::Key::CloseBracket)),
+            (KeyCode::Backquote, Some(egui::Key::Backtick)),
+            (KeyCode::Quote, Some(egui::Key::Quote)),
+            // the numpad, as the keys of the main block
+            (KeyCode::Numpad0, Some(egui::Key::Num0)),
+            (KeyCode::Numpad9, Some(egui::Key::Num9)),
+            (KeyCode::NumpadAdd, Some(egui::Key::Plus)),
+            (KeyCode::NumpadSubtract, Some(egui::Key::Minus)),
+            (KeyCode::NumpadDivide, Some(egui::Key::Slash)),
+            (KeyCode::NumpadDecimal, Some(egui::Key::Period)),
+            (KeyCode::NumpadComma, Some(egui::Key::Comma)),
+            (KeyCode::NumpadEqual, Some(egui::Key::Equals)),
+            (KeyCode::NumpadEnter, Some(egui::Key::Enter)),
+            (KeyCode::NumpadBackspace, Some(egui::Key::Backspace)),
+            (KeyCode::NumpadMultiply, None),
+            // letters and digits
+            (KeyCode::KeyA, Some(egui::Key::A)),
+            (KeyCode::KeyQ, Some(egui::Key::Q)),
+            (KeyCode::KeyZ, Some(egui::Key::Z)),
+            (KeyCode::Digit0, Some(egui::Key::Num0)),
+            // named keys
+            (KeyCode::Copy, Some(egui::Key::Copy)),
+            (KeyCode::Cut, Some(egui::Key::Cut)),
+            (KeyCode::Paste, Some(egui::Key::Paste)),
+            (KeyCode::Help, Some(egui::Key::Insert)),
+            (KeyCode::F1, Some(egui::Key::F1)),
+            (KeyCode::F13, Some(egui::Key::F13)),
+            (KeyCode::F24, Some(egui::Key::F24)),
+            (KeyCode::F35, Some(egui::Key::F35)),
+            (KeyCode::ShiftLeft, None),
+            (KeyCode::AudioVolumeUp, None),
+        ];
+        for (key, expected) in cases {
+            assert_eq!(translate_physical_key(&key), expected, "{key:?}");
+        }
+    }
+}
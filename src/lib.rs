@@ -1,8 +1,29 @@
+#[macro_use]
+mod logging;
+
+mod clipboard;
+mod decorations;
+mod error;
+mod input;
+mod keyboard;
+mod opener;
+mod options;
 mod plugin;
 mod renderer;
+mod scheduler;
+mod touch;
 mod utils;
+mod viewport;
 
+pub use clipboard::{Clipboard, MemoryClipboard, SystemClipboard};
+pub use decorations::{resize_edges, TitleBar};
+pub use error::Error;
+pub use opener::{default_url_filter, SystemUrlOpener, UrlOpener};
+pub use options::{GpuOptions, RendererOptions};
 pub use plugin::{AppHandleExt, Builder, WheelEvent};
+pub use renderer::OffscreenRenderer;
 
 // re-export for convenience
 pub use egui;
+pub use egui_wgpu;
+pub use egui_wgpu::wgpu;
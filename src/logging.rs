@@ -0,0 +1,57 @@
+//! Diagnostics of the plugin, routed through `tracing` with the `tracing` feature.
+//!
+//! Without the feature, the macros compile to nothing, but still type check their arguments.
+//! `tracing` forwards to `log` when no subscriber is installed, e.g. for `tauri-plugin-log`.
+
+macro_rules! log_event {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = format_args!($($arg)+);
+        }
+    }};
+}
+
+macro_rules! error {
+    ($($arg:tt)+) => { log_event!(error, $($arg)+) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)+) => { log_event!(warn, $($arg)+) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)+) => { log_event!(debug, $($arg)+) };
+}
+
+/// Enters a span for everything happening to the egui window `$label`,
+/// until the returned guard is dropped.
+macro_rules! window_span {
+    ($label:expr) => {{
+        #[cfg(feature = "tracing")]
+        let guard = tracing::debug_span!("egui_window", label = %$label).entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = {
+            let _ = &$label;
+            $crate::logging::NoSpan
+        };
+        guard
+    }};
+}
+
+/// Enters a span for rendering one frame, until the returned guard is dropped.
+macro_rules! frame_span {
+    () => {{
+        #[cfg(feature = "tracing")]
+        let guard = tracing::trace_span!("egui_frame").entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = $crate::logging::NoSpan;
+        guard
+    }};
+}
+
+/// Stands in for the span guards without the `tracing` feature.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
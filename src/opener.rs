@@ -0,0 +1,37 @@
+use crate::Error;
+
+/// Opens the URLs of egui hyperlinks, e.g. from `ui.hyperlink(...)`.
+///
+/// Set a custom one with [`crate::Builder::url_opener`], the default is [`SystemUrlOpener`].
+pub trait UrlOpener: Send + Sync {
+    /// `new_tab` is egui's hint on whether the URL should be opened
+    /// in a new tab instead of replacing the current page.
+    fn open_url(&self, url: &str, new_tab: bool) -> Result<(), Error>;
+}
+
+/// Opens URLs with the default application of the operating system.
+///
+/// The system opener always hands the URL over to another application,
+/// so `new_tab` has no effect here: browsers open it in a new tab or window.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemUrlOpener;
+
+impl UrlOpener for SystemUrlOpener {
+    fn open_url(&self, url: &str, _new_tab: bool) -> Result<(), Error> {
+        open::that_detached(url)?;
+        Ok(())
+    }
+}
+
+/// The URL filter used unless one is set with [`crate::Builder::url_filter`].
+///
+/// Only allows `http`, `https` and `mailto` URLs.
+pub fn default_url_filter(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else {
+        return false;
+    };
+    matches!(
+        scheme.to_ascii_lowercase().as_str(),
+        "http" | "https" | "mailto"
+    )
+}
@@ -0,0 +1,151 @@
+use egui_wgpu::wgpu;
+
+/// How the GPU device shared by all egui windows is created.
+///
+/// Set with [`crate::Builder::gpu_options`]. As the device is shared,
+/// these options apply to every window and cannot be changed per window.
+#[derive(Debug, Clone)]
+pub struct GpuOptions {
+    /// The graphics APIs wgpu may use, e.g. [`wgpu::Backends::VULKAN`].
+    ///
+    /// Only the default, all of them, falls back to the GL backend.
+    pub backends: wgpu::Backends,
+    pub power_preference: wgpu::PowerPreference,
+    /// Only use a software adapter, e.g. lavapipe or WARP.
+    ///
+    /// Otherwise, when no adapter matches these options, e.g. in VMs without a GPU,
+    /// a software adapter and then, with the default [`GpuOptions::backends`],
+    /// the GL backend are tried instead.
+    pub force_fallback_adapter: bool,
+    /// Features the device must support, e.g. for custom wgpu rendering inside egui.
+    pub required_features: wgpu::Features,
+    /// Limits the device must support.
+    ///
+    /// `None` uses the wgpu defaults, raised to the adapter's texture size limits.
+    pub required_limits: Option<wgpu::Limits>,
+}
+
+impl Default for GpuOptions {
+    fn default() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: false,
+            required_features: wgpu::Features::empty(),
+            required_limits: None,
+        }
+    }
+}
+
+impl GpuOptions {
+    pub(crate) fn create_instance(&self) -> wgpu::Instance {
+        wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: self.backends,
+            ..Default::default()
+        })
+    }
+
+    /// The options to try one after the other until an adapter is found:
+    /// these options, a software adapter, and finally the GL backend.
+    ///
+    /// Backends picked by the app are kept to, so GL is only tried with the default ones.
+    pub(crate) fn fallback_chain(&self) -> Vec<GpuOptions> {
+        if self.force_fallback_adapter {
+            return vec![self.clone()];
+        }
+        let mut chain = vec![
+            self.clone(),
+            GpuOptions {
+                force_fallback_adapter: true,
+                ..self.clone()
+            },
+        ];
+        if self.backends == GpuOptions::default().backends {
+            chain.push(GpuOptions {
+                backends: wgpu::Backends::GL,
+                ..self.clone()
+            });
+        }
+        chain
+    }
+}
+
+/// How an egui window is rendered.
+///
+/// The default for all windows is set with [`crate::Builder::renderer_options`],
+/// and can be overridden with [`crate::AppHandleExt::start_egui_for_window_with_options`].
+#[derive(Debug, Clone, Copy)]
+pub struct RendererOptions {
+    /// e.g. [`wgpu::PresentMode::AutoVsync`], [`wgpu::PresentMode::Mailbox`]
+    /// or [`wgpu::PresentMode::Immediate`].
+    ///
+    /// `None`, or a mode the window doesn't support, uses the first supported one.
+    pub present_mode: Option<wgpu::PresentMode>,
+    /// The number of samples for multisample anti-aliasing, `1` turns it off.
+    ///
+    /// egui already anti-aliases its shapes, this is mostly useful for custom 3D rendering.
+    /// Falls back to `1` if the device doesn't support the sample count for the surface format.
+    /// Only `1` and `4` are always supported, other counts need the
+    /// [`wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] feature in
+    /// [`GpuOptions::required_features`] and an adapter supporting them.
+    pub msaa_samples: u32,
+    /// How many frames may be queued for presentation, lower values reduce latency.
+    pub desired_maximum_frame_latency: u32,
+}
+
+impl Default for RendererOptions {
+    fn default() -> Self {
+        Self {
+            present_mode: None,
+            msaa_samples: 1,
+            desired_maximum_frame_latency: 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The backends and whether only a software adapter is used, for each step of the chain.
+    fn steps(options: GpuOptions) -> Vec<(wgpu::Backends, bool)> {
+        options
+            .fallback_chain()
+            .into_iter()
+            .map(|options| (options.backends, options.force_fallback_adapter))
+            .collect()
+    }
+
+    #[test]
+    fn default_backends_fall_back_to_gl() {
+        assert_eq!(
+            steps(GpuOptions::default()),
+            [
+                (wgpu::Backends::all(), false),
+                (wgpu::Backends::all(), true),
+                (wgpu::Backends::GL, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn picked_backends_are_kept_to() {
+        let options = GpuOptions {
+            backends: wgpu::Backends::VULKAN,
+            ..Default::default()
+        };
+        assert_eq!(
+            steps(options),
+            [(wgpu::Backends::VULKAN, false), (wgpu::Backends::VULKAN, true)]
+        );
+    }
+
+    #[test]
+    fn forced_software_adapter_has_no_fallback() {
+        let options = GpuOptions {
+            force_fallback_adapter: true,
+            ..Default::default()
+        };
+        assert_eq!(steps(options), [(wgpu::Backends::all(), true)]);
+    }
+}
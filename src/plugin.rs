@@ -1,5 +1,4 @@
-use anyhow::Error;
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 use std::time::Instant;
 use serde::{Deserialize, Serialize};
@@ -11,39 +10,222 @@ use tauri_runtime::UserEvent;
 use tauri_runtime_wry::{Context, PluginBuilder, WindowMessage};
 use tauri_runtime_wry::{EventLoopIterationContext, Message, Plugin, WebContextStore};
 
-use tauri_runtime_wry::tao::event::{
-    ElementState, Event, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent as TaoWindowEvent,
-};
+use tauri_runtime_wry::tao::event::{ElementState, Event, KeyEvent, WindowEvent as TaoWindowEvent};
 use tauri_runtime_wry::tao::event_loop::{ControlFlow, EventLoopProxy, EventLoopWindowTarget};
-use tauri_runtime_wry::tao::keyboard::{Key, KeyCode};
+use tauri_runtime_wry::tao::keyboard::Key;
+use tauri_runtime_wry::tao::window::Theme as TaoTheme;
 
-use crate::renderer::Renderer;
-use crate::utils::{get_id_from_tao_id, get_label_from_tao_id};
+use crate::clipboard::{Clipboard, SystemClipboard};
+use crate::keyboard::{translate_key_event, translate_logical_key, translate_physical_key};
+use crate::error::Error;
+use crate::input::WindowInput;
+use crate::opener::{default_url_filter, SystemUrlOpener, UrlOpener};
+use crate::options::{GpuOptions, RendererOptions};
+use crate::renderer::{EguiRenderer, Gpu, Renderer};
+use crate::scheduler::RedrawScheduler;
+use crate::viewport::{translate_viewport_command, viewport_label, window_builder};
+use crate::utils::{get_id_from_label, get_id_from_tao_id, get_label_from_tao_id};
 
 /// A map of EguiWindow instances, keyed by their Tauri window label.
 type EguiWindowMap = Arc<Mutex<HashMap<String, EguiWindow>>>;
 
+/// The Tauri windows showing the viewports of one egui context, to route its repaints.
+type RepaintTargets = Arc<Mutex<HashMap<egui::ViewportId, tauri_runtime::window::WindowId>>>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WheelEvent {
     pub delta_x: i64,
     pub delta_y: i64,
 }
 
-pub struct StagingWindowWrapper {
-    pub window: Option<(String, EguiWindow)>,
+/// The payload of the `egui_theme` event, e.g. `{ "label": "main", "theme": "dark" }`.
+#[derive(Debug, Clone, Deserialize)]
+struct ThemeEvent {
+    label: String,
+    theme: ThemeName,
+}
+
+/// The payload of the `egui_redraw` event, e.g. `{ "label": "main" }` like for `egui_theme`,
+/// or just the label. Without a label, e.g. `null` or `{}`, all egui windows are redrawn.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum RedrawEvent {
+    Label(Option<String>),
+    Window { label: Option<String> },
+}
+
+impl RedrawEvent {
+    fn label(self) -> Option<String> {
+        match self {
+            RedrawEvent::Label(label) | RedrawEvent::Window { label } => label,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ThemeName {
+    System,
+    Light,
+    Dark,
+}
+
+impl From<ThemeName> for egui::ThemePreference {
+    fn from(theme: ThemeName) -> Self {
+        match theme {
+            ThemeName::System => egui::ThemePreference::System,
+            ThemeName::Light => egui::ThemePreference::Light,
+            ThemeName::Dark => egui::ThemePreference::Dark,
+        }
+    }
+}
+
+/// Windows registered with `start_egui_for_window` that the event loop hasn't picked up yet.
+struct StagingWindowWrapper {
+    windows: HashMap<String, EguiWindow>,
+}
+
+impl StagingWindowWrapper {
+    /// Moves the staged windows known to the event loop into the live map.
+    ///
+    /// From then on, repaints requested on their egui context redraw the window.
+    fn promote<T: UserEvent>(
+        &mut self,
+        windows: &mut HashMap<String, EguiWindow>,
+        scheduler: &RedrawScheduler<T>,
+        context: &EventLoopIterationContext<'_, T>,
+    ) {
+        let labels: Vec<String> = self.windows.keys().cloned().collect();
+        for label in labels {
+            let Some(win_id) = get_id_from_label(&label, context) else {
+                continue;
+            };
+            let mut egui_win = self.windows.remove(&label).unwrap();
+            egui_win.win_id = Some(win_id);
+
+            // immediate viewports are drawn by the window of their parent, see `render_immediate_viewport`
+            if egui_win.viewport.class != egui::ViewportClass::Immediate {
+                egui_win
+                    .repaint_targets
+                    .lock()
+                    .unwrap()
+                    .insert(egui_win.viewport.id, win_id);
+            }
+            // the windows of the other viewports share the context, and so its callback
+            if egui_win.viewport.id == egui::ViewportId::ROOT {
+                let scheduler = scheduler.clone();
+                let repaint_targets = egui_win.repaint_targets.clone();
+                egui_win.context.set_request_repaint_callback(move |info| {
+                    let repaint_targets = repaint_targets.lock().unwrap();
+                    let win_id = repaint_targets
+                        .get(&info.viewport_id)
+                        .or_else(|| repaint_targets.get(&egui::ViewportId::ROOT));
+                    if let Some(win_id) = win_id {
+                        scheduler.schedule(*win_id, info.delay);
+                    }
+                });
+            }
+            egui_win.request_repaint();
+
+            debug!("egui started for window {}", label);
+            windows.insert(label, egui_win);
+        }
+    }
 }
 
 type StagingWindow = Arc<Mutex<StagingWindowWrapper>>;
 
+/// An app-provided callback for errors that cannot be handled by the plugin itself.
+/// It receives the label of the affected window.
+type ErrorHandler = Arc<dyn Fn(&str, Error) + Send + Sync>;
+
+/// Hands a fatal rendering error to the app, or logs it without an error handler.
+///
+/// Call it without holding the window maps, the handler may call back into the plugin.
+fn report_render_error(on_error: Option<&ErrorHandler>, label: &str, e: wgpu::SurfaceError) {
+    match on_error {
+        Some(on_error) => on_error(label, e.into()),
+        None => error!("Error rendering window: {}", e),
+    }
+}
+
+/// The clipboard shared by all egui windows.
+type SharedClipboard = Arc<Mutex<Box<dyn Clipboard>>>;
+
+/// Opens the URLs of egui hyperlinks.
+type SharedUrlOpener = Arc<dyn UrlOpener>;
+
+/// Decides which URLs egui is allowed to open.
+type UrlFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// The GPU context shared by all egui windows, created along with the first one.
+type SharedGpu = Arc<Mutex<Option<Arc<Gpu>>>>;
+
 // The builder pattern is mandatorily needed for a Tauri `.wry_plugin()`
 // It sets up the tauri state + offers a hook into the event system
 pub struct Builder {
     app: AppHandle,
+    on_error: Option<ErrorHandler>,
+    clipboard: Option<Box<dyn Clipboard>>,
+    url_opener: Option<SharedUrlOpener>,
+    url_filter: Option<UrlFilter>,
+    gpu_options: GpuOptions,
+    renderer_options: RendererOptions,
 }
 
 impl Builder {
     pub fn new(app: AppHandle) -> Self {
-        Self { app }
+        Self {
+            app,
+            on_error: None,
+            clipboard: None,
+            url_opener: None,
+            url_filter: None,
+            gpu_options: GpuOptions::default(),
+            renderer_options: RendererOptions::default(),
+        }
+    }
+
+    /// Sets how the GPU device shared by all egui windows is created.
+    pub fn gpu_options(mut self, gpu_options: GpuOptions) -> Self {
+        self.gpu_options = gpu_options;
+        self
+    }
+
+    /// Sets how egui windows are rendered, unless overridden when starting egui for a window.
+    pub fn renderer_options(mut self, renderer_options: RendererOptions) -> Self {
+        self.renderer_options = renderer_options;
+        self
+    }
+
+    /// Sets the clipboard used for copy, cut and paste. Defaults to the [`SystemClipboard`].
+    pub fn clipboard<C: Clipboard + 'static>(mut self, clipboard: C) -> Self {
+        self.clipboard = Some(Box::new(clipboard));
+        self
+    }
+
+    /// Sets how the URLs of egui hyperlinks are opened. Defaults to the [`SystemUrlOpener`].
+    pub fn url_opener<O: UrlOpener + 'static>(mut self, url_opener: O) -> Self {
+        self.url_opener = Some(Arc::new(url_opener));
+        self
+    }
+
+    /// Sets which URLs egui hyperlinks are allowed to open, the filter returns `true` to allow one.
+    ///
+    /// Defaults to [`crate::default_url_filter`], which only allows `http`, `https` and `mailto`.
+    pub fn url_filter<F: Fn(&str) -> bool + Send + Sync + 'static>(mut self, url_filter: F) -> Self {
+        self.url_filter = Some(Arc::new(url_filter));
+        self
+    }
+
+    /// Registers a callback for fatal rendering errors, like the GPU running out of memory.
+    ///
+    /// Recoverable surface errors (lost or outdated surfaces, timeouts) are handled internally.
+    /// A window stops redrawing on its own after a fatal error, which is reported once
+    /// until the window renders again, e.g. on the next input.
+    pub fn on_error<F: Fn(&str, Error) + Send + Sync + 'static>(mut self, on_error: F) -> Self {
+        self.on_error = Some(Arc::new(on_error));
+        self
     }
 }
 
@@ -52,10 +234,57 @@ impl<T: UserEvent> PluginBuilder<T> for Builder {
 
     fn build(self, _: Context<T>) -> Self::Plugin {
         let egui_window_map: EguiWindowMap = Arc::new(Mutex::new(HashMap::new()));
-        let staging_window: StagingWindow = Arc::new(Mutex::new(StagingWindowWrapper { window: None }));
+        let staging_window: StagingWindow = Arc::new(Mutex::new(StagingWindowWrapper { windows: HashMap::new() }));
+        let gpu: SharedGpu = Arc::new(Mutex::new(None));
+        let clipboard: SharedClipboard = Arc::new(Mutex::new(
+            self.clipboard
+                .unwrap_or_else(|| Box::new(SystemClipboard::new())),
+        ));
         self.app.manage(egui_window_map.clone());
         self.app.manage(staging_window.clone());
-        EguiPlugin::new(self.app.clone(), staging_window, egui_window_map)
+        self.app.manage(gpu.clone());
+        let url_opener: SharedUrlOpener = self
+            .url_opener
+            .unwrap_or_else(|| Arc::new(SystemUrlOpener));
+        let url_filter: UrlFilter = self
+            .url_filter
+            .unwrap_or_else(|| Arc::new(default_url_filter));
+        self.app.manage(clipboard);
+        self.app.manage(url_opener);
+        self.app.manage(url_filter);
+        self.app.manage(self.gpu_options);
+        self.app.manage(self.renderer_options);
+
+        // lets other threads and the webview request redraws, see `AppHandleExt::request_egui_redraw`
+        let redraw_app_handle = self.app.clone();
+        self.app.listen("egui_redraw", move |event| {
+            let result = match serde_json::from_str::<RedrawEvent>(event.payload()) {
+                Ok(redraw) => match redraw.label() {
+                    Some(label) => redraw_app_handle.request_egui_redraw(&label),
+                    None => redraw_app_handle.request_egui_redraw_all(),
+                },
+                // not taken as a redraw of all windows, which may not be what was asked for
+                Err(e) => Err(Error::Other(Box::new(e))),
+            };
+            if let Err(e) = result {
+                warn!("Error handling egui_redraw event: {}", e);
+            }
+        });
+
+        // lets the webview pick the theme of an egui window, see `AppHandleExt::set_egui_theme`
+        let theme_app_handle = self.app.clone();
+        self.app.listen("egui_theme", move |event| {
+            let result = match serde_json::from_str::<ThemeEvent>(event.payload()) {
+                Ok(ThemeEvent { label, theme }) => {
+                    theme_app_handle.set_egui_theme(&label, theme.into())
+                }
+                Err(e) => Err(Error::Other(Box::new(e))),
+            };
+            if let Err(e) = result {
+                warn!("Error handling egui_theme event: {}", e);
+            }
+        });
+        EguiPlugin::new(self.app.clone(), staging_window, egui_window_map, gpu, self.on_error)
     }
 }
 
@@ -63,17 +292,30 @@ pub struct EguiPlugin<T: UserEvent> {
     app: AppHandle,
     staging_window: StagingWindow,
     windows: EguiWindowMap,
+    gpu: SharedGpu,
+    on_error: Option<ErrorHandler>,
+    // created along with the first event, as it needs the event loop proxy
+    scheduler: Option<RedrawScheduler<T>>,
     is_rdev_loop_running: Arc<AtomicBool>,
     rdev_thread_join_handle: Option<std::thread::JoinHandle<()>>,
     _phantom: std::marker::PhantomData<T>, // this does nothing, just keeps compiler happy
 }
 
 impl<T: UserEvent> EguiPlugin<T> {
-    fn new(app: AppHandle,staging_window: StagingWindow, windows: EguiWindowMap) -> Self {
+    fn new(
+        app: AppHandle,
+        staging_window: StagingWindow,
+        windows: EguiWindowMap,
+        gpu: SharedGpu,
+        on_error: Option<ErrorHandler>,
+    ) -> Self {
         Self {
             app,
             staging_window,
             windows,
+            gpu,
+            on_error,
+            scheduler: None,
             is_rdev_loop_running: Arc::new(AtomicBool::new(false)),
             rdev_thread_join_handle: None,
             _phantom: std::marker::PhantomData,
@@ -81,6 +323,116 @@ impl<T: UserEvent> EguiPlugin<T> {
     }
 }
 
+impl<T: UserEvent> EguiPlugin<T> {
+    /// Runs the UI of an egui window and renders the resulting frame.
+    fn redraw(
+        &self,
+        label: &str,
+        win_id: tauri_runtime::window::WindowId,
+        proxy: &EventLoopProxy<Message<T>>,
+        scheduler: &RedrawScheduler<T>,
+    ) {
+        let _window_span = window_span!(label);
+        let _frame_span = frame_span!();
+
+        let mut windows = self.windows.lock().unwrap();
+        let Some(egui_win) = windows.get_mut(label) else {
+            return;
+        };
+        // drawn along with their parent, which runs their UI
+        if egui_win.viewport.class == egui::ViewportClass::Immediate {
+            egui_win.request_repaint();
+            return;
+        }
+        // nothing to see, input is kept for once the window is restored
+        if egui_win.is_hidden() {
+            return;
+        }
+
+        // Get the egui context from the EguiWindow
+        let raw_input = egui_win.take_egui_input();
+        let context = egui_win.context.clone();
+        let viewport_id = egui_win.viewport.id;
+
+        // `ui_fn` runs without holding the lock, so it can call back into the plugin,
+        // e.g. to stop egui for this window or to start it for another one.
+        let mut ui_fn = egui_win.ui_fn.take();
+        drop(windows);
+
+        // Run `ui_fn` (which describes the UI)
+        // This function comes from the tauri app itself and runs every frame.
+        // The `ctx.run()` method processes the inputs and drawings and returns output:
+        // 1. texture info to give to GPU
+        // 2. platform_output to handl events like cursor, copy-paste etc.
+        // 3. pixels_per_point which is the scale factor for rendering
+        let egui::FullOutput {
+            textures_delta,
+            shapes,
+            pixels_per_point,
+            platform_output,
+            viewport_output,
+            ..
+        } = context.run(raw_input, |ctx| {
+            if let Some(ui_fn) = ui_fn.as_mut() {
+                ui_fn(ctx);
+            }
+        });
+
+        let mut windows = self.windows.lock().unwrap();
+        let Some(egui_win) = windows.get_mut(label) else {
+            // egui was stopped for this window while running the UI
+            return;
+        };
+        if egui_win.context != context {
+            // egui was stopped and restarted for this window while running the UI
+            return;
+        }
+        if egui_win.ui_fn.is_none() {
+            egui_win.ui_fn = ui_fn;
+        }
+
+        // Handle platform output (clipboard, cursor, links)
+        egui_win.handle_platform_output(&platform_output, win_id, proxy);
+
+        // egui tells us when it wants the next frame, e.g. for animations
+        // or `ctx.request_repaint_after(..)`, `Duration::MAX` means never
+        let mut repaint_delay = viewport_output
+            .get(&viewport_id)
+            .map_or(std::time::Duration::MAX, |output| output.repaint_delay);
+
+        // Finally we render textures, paint jobs, etc. using the GPU
+        let mut render_error = None;
+        match egui_win.paint(shapes, pixels_per_point, textures_delta) {
+            Ok(()) => egui_win.render_failed = false,
+            // the surface was reconfigured, or the frame was dropped, draw it again
+            Err(
+                wgpu::SurfaceError::Lost
+                | wgpu::SurfaceError::Outdated
+                | wgpu::SurfaceError::Timeout,
+            ) => {
+                repaint_delay = std::time::Duration::ZERO;
+            }
+            Err(e) => render_error = egui_win.fail_render(e),
+        }
+        let render_failed = egui_win.render_failed;
+        drop(windows);
+
+        // the handler may call back into the plugin, e.g. to stop egui for the window
+        if let Some(e) = render_error {
+            report_render_error(self.on_error.as_ref(), label, e);
+        }
+
+        // the windows of the other viewports, and the commands for all of them
+        sync_viewport_windows(&self.app, label, &context, &viewport_output, proxy);
+
+        // Request another redraw, right away or once the delay has passed,
+        // a window that can't render only draws again on input
+        if !render_failed {
+            scheduler.schedule(win_id, repaint_delay);
+        }
+    }
+}
+
 impl<T: UserEvent> Plugin<T> for EguiPlugin<T> {
     fn on_event(
         &mut self,
@@ -94,14 +446,29 @@ impl<T: UserEvent> Plugin<T> for EguiPlugin<T> {
         if let Event::LoopDestroyed = event {
             self.is_rdev_loop_running.store(false, Ordering::SeqCst);
 
-            println!("==== we got loop destroyed, waiting for the rdev thread to join ====");
+            debug!("Event loop destroyed, waiting for the rdev thread to join");
             if let Some(handle) = self.rdev_thread_join_handle.take() {
                 let _ = handle.join(); 
             }
+            // release the shared device once no window can render anymore
+            self.gpu.lock().unwrap().take();
             return false;
         }
 
 
+        if self.scheduler.is_none() {
+            self.scheduler = Some(RedrawScheduler::new(proxy.clone()));
+
+            // egui calls it on the thread running the UI, which is this one
+            let app = self.app.clone();
+            let proxy = proxy.clone();
+            let on_error = self.on_error.clone();
+            egui::Context::set_immediate_viewport_renderer(move |context, viewport| {
+                render_immediate_viewport(&app, &proxy, on_error.as_ref(), context, viewport);
+            });
+        }
+        let scheduler = self.scheduler.clone().unwrap();
+
         match event {
             Event::WindowEvent {
                 event, window_id, ..
@@ -109,64 +476,63 @@ impl<T: UserEvent> Plugin<T> for EguiPlugin<T> {
 
                 if let Some(label) = get_label_from_tao_id(window_id, &context) {
                     // we inject a rdev event loop to handle other app window's events
-                    if let Some(win_id) = get_id_from_tao_id(window_id, &context) {
-                        if !self.is_rdev_loop_running.load(Ordering::SeqCst) {
-                            let redraw_proxy = proxy.clone();
-                            self.is_rdev_loop_running.store(true, Ordering::SeqCst);
-                            let is_rdev_loop_running = self.is_rdev_loop_running.clone();
-                            let rdev_app_handle = self.app.clone();
-                            let redraw_app_handle = self.app.clone();
-                            let handle = std::thread::spawn(move || {
-                                if let Err(e) = rdev::listen(move |event| {
-                                    if !is_rdev_loop_running.load(Ordering::SeqCst) {
-                                        return;
-                                    }
-                                    if let rdev::EventType::Wheel { delta_x, delta_y } = event.event_type {
-                                        let _ = rdev_app_handle.emit("global_wheel_event", WheelEvent { delta_x, delta_y });
-                                    }
-                                }) {
-                                    eprintln!("Error listening to rdev events: {:?}", e);
+                    if !self.is_rdev_loop_running.load(Ordering::SeqCst) {
+                        self.is_rdev_loop_running.store(true, Ordering::SeqCst);
+                        let is_rdev_loop_running = self.is_rdev_loop_running.clone();
+                        let rdev_app_handle = self.app.clone();
+                        let handle = std::thread::spawn(move || {
+                            if let Err(e) = rdev::listen(move |event| {
+                                if !is_rdev_loop_running.load(Ordering::SeqCst) {
+                                    return;
                                 }
-                            });
-                            self.rdev_thread_join_handle = Some(handle);
-
-                            // we let the up layer logic to request redraw when needed
-                            std::thread::spawn(move || {
-                                redraw_app_handle.listen("egui_redraw", move |_| {
-                                    let _ = redraw_proxy.send_event(
-                                        Message::Window(win_id, WindowMessage::RequestRedraw),
-                                    );
-
-                                });
-                            });
-                        }
+                                if let rdev::EventType::Wheel { delta_x, delta_y } = event.event_type {
+                                    let _ = rdev_app_handle.emit("global_wheel_event", WheelEvent { delta_x, delta_y });
+                                }
+                            }) {
+                                error!("Error listening to rdev events: {:?}", e);
+                            }
+                        });
+                        self.rdev_thread_join_handle = Some(handle);
                     }
 
+                    let _window_span = window_span!(label);
+
                     let mut windows = self.windows.lock().unwrap();
-                    if !windows.contains_key(&label) {
-                        let mut staging_window = self.staging_window.lock().unwrap();
-                        let staging_lable_opt = staging_window.window.as_ref()
-                            .map(|(l, _w)| l.clone());
-                        if let Some(staging_label) = staging_lable_opt {
-                            if label == staging_label {
-                                // extract the window from the staging window
-                                let (label, window) = staging_window.window.take().unwrap();
-                                windows.insert(label, window);
-                            } 
-                        }
-                    }
+                    self.staging_window
+                        .lock()
+                        .unwrap()
+                        .promote(&mut windows, &scheduler, &context);
                     if let Some(egui_win) = windows.get_mut(&label) {
                         match event {
                             TaoWindowEvent::Resized(size) => {
                                 egui_win.size = PhysicalSize::new(size.width, size.height);
-                                egui_win.renderer.resize(size.width, size.height);
+                                // maximizing, minimizing and fullscreen all resize the window
+                                egui_win.window_state = WindowState::of(&egui_win.window);
+                                // minimized windows can be resized to nothing, which the surface can't be
+                                if size.width > 0 && size.height > 0 {
+                                    egui_win.renderer.resize(size.width, size.height);
+                                }
+                                if !egui_win.is_hidden() {
+                                    egui_win.request_repaint();
+                                }
+                                return true;
+                            }
+                            // the app closes other viewports by no longer showing them, like in egui-winit
+                            TaoWindowEvent::CloseRequested
+                                if egui_win.viewport.id != egui::ViewportId::ROOT =>
+                            {
+                                egui_win.request_close();
                                 return true;
                             }
                             TaoWindowEvent::Destroyed => {
+                                debug!("Window destroyed, stopping egui");
+                                let mut egui_win = windows.remove(&label).unwrap();
+                                // `on_destroy` may call back into the plugin
+                                drop(windows);
+                                egui_win.close_viewports(&self.app);
                                 if let Some(mut on_destroy) = egui_win.on_destroy.take() {
                                     on_destroy(label.clone());
                                 }
-                                windows.remove(&label);
                                 return false;
                             }
                             _ => {
@@ -193,75 +559,15 @@ impl<T: UserEvent> Plugin<T> for EguiPlugin<T> {
             }
             Event::RedrawRequested(window_id) => {
                 if let Some(label) = get_label_from_tao_id(window_id, &context) {
-                    let mut windows = self.windows.lock().unwrap();
-                    if !windows.contains_key(&label) {
-                        let mut staging_window = self.staging_window.lock().unwrap();
-                        let staging_lable_opt = staging_window.window.as_ref()
-                            .map(|(l, _w)| l.clone());
-                        if let Some(staging_label) = staging_lable_opt {
-                            if label == staging_label {
-                                // extract the window from the staging window
-                                let (label, window) = staging_window.window.take().unwrap();
-                                windows.insert(label, window);
-                            }
-                        }
-                    }
-                    if let Some(egui_win) = windows.get_mut(&label) {
-                        // Get the egui context from the EguiWindow
-                        let raw_input = egui_win.take_egui_input();
-
-                        // Run `ui_fn` (which describes the UI)
-                        // This function comes from the tauri app itself and runs every frame.
-                        // The `ctx.run()` method processes the inputs and drawings and returns output:
-                        // 1. texture info to give to GPU
-                        // 2. platform_output to handl events like cursor, copy-paste etc.
-                        // 3. pixels_per_point which is the scale factor for rendering
-                        let egui::FullOutput {
-                            textures_delta,
-                            shapes,
-                            pixels_per_point,
-                            platform_output,
-                            ..
-                        } = egui_win.context.run(raw_input, |ctx| {
-                            (egui_win.ui_fn)(ctx);
-                        });
-
-                        // Handle platform output (clipboard, cursor, links)
-                        if let Some(win_id) = get_id_from_tao_id(window_id, &context) {
-                            if let Err(e) =
-                                egui_win.handle_platform_output(&platform_output, win_id, &proxy)
-                            {
-                                eprintln!("Error handling platform output: {}", e);
-                            }
-                        }
+                    if let Some(win_id) = get_id_from_tao_id(window_id, &context) {
+                        let mut windows = self.windows.lock().unwrap();
+                        self.staging_window
+                            .lock()
+                            .unwrap()
+                            .promote(&mut windows, &scheduler, &context);
+                        drop(windows);
 
-                        // Converts all the shapes into triangles meshes
-                        let paint_jobs = egui_win.context.tessellate(shapes, pixels_per_point);
-
-                        let width = egui_win.size.width;
-                        let height = egui_win.size.height;
-
-                        let screen_descriptor = egui_wgpu::ScreenDescriptor {
-                            size_in_pixels: [width, height],
-                            pixels_per_point: pixels_per_point,
-                        };
-
-                        // Finally we render textures, paint jobs, etc. using the GPU
-                        egui_win.renderer.render_frame(
-                            screen_descriptor,
-                            paint_jobs,
-                            textures_delta,
-                        );
-
-                        // Check if egui wants us to repaint and request another redraw
-                        if egui_win.context.has_requested_repaint() {
-                            let win_id = get_id_from_tao_id(window_id, &context);
-                            if let Some(id) = win_id {
-                                proxy
-                                    .send_event(Message::Window(id, WindowMessage::RequestRedraw))
-                                    .ok();
-                            }
-                        }
+                        self.redraw(&label, win_id, proxy, &scheduler);
                     }
                 }
             }
@@ -273,19 +579,76 @@ impl<T: UserEvent> Plugin<T> for EguiPlugin<T> {
     }
 }
 
+/// The closure describing the UI of an egui window, run every frame.
+type UiFn = Box<dyn FnMut(&egui::Context)>;
+
 /// A collection egui context, renderer and a UI function
 struct EguiWindow {
     label: String,
+    window: tauri::Window,
     context: egui::Context,
     renderer: Renderer,
     size: PhysicalSize<u32>,
-    ui_fn: Box<dyn FnMut(&egui::Context)>,
+    // only `None` while the UI is running
+    ui_fn: Option<UiFn>,
     on_destroy: Option<Box<dyn FnMut(String)>>,
+    clipboard: SharedClipboard,
+    url_opener: SharedUrlOpener,
+    url_filter: UrlFilter,
     start_time: Instant,
-    egui_input: egui::RawInput,
-    pointer_pos: Option<egui::Pos2>,
-    scale_factor: f32,
-    modifiers: egui::Modifiers,
+    input: WindowInput,
+    // the cursor last set on the window, `None` until the first frame
+    cursor_icon: Option<egui::CursorIcon>,
+    // a fatal render error was reported, and no frame rendered since
+    render_failed: bool,
+    // the state egui sees in `ViewportInfo`, e.g. for a maximize button
+    window_state: WindowState,
+    // followed by egui unless the app picks a theme, see `AppHandleExt::set_egui_theme`
+    system_theme: Option<egui::Theme>,
+    // `None` until the event loop picked up the window
+    win_id: Option<tauri_runtime::window::WindowId>,
+    viewport: Viewport,
+    // shared with the windows of the other viewports of the context
+    repaint_targets: RepaintTargets,
+    // also used for the windows of the other viewports
+    renderer_options: RendererOptions,
+}
+
+/// The egui viewport shown in a window, windows started by the app show the root viewport.
+struct Viewport {
+    id: egui::ViewportId,
+    parent: egui::ViewportId,
+    class: egui::ViewportClass,
+    // what the window was opened or last updated with
+    builder: egui::ViewportBuilder,
+}
+
+impl Viewport {
+    fn root() -> Self {
+        Self {
+            id: egui::ViewportId::ROOT,
+            parent: egui::ViewportId::ROOT,
+            class: egui::ViewportClass::Root,
+            builder: egui::ViewportBuilder::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WindowState {
+    maximized: bool,
+    minimized: bool,
+    fullscreen: bool,
+}
+
+impl WindowState {
+    fn of(window: &tauri::Window) -> Self {
+        Self {
+            maximized: window.is_maximized().unwrap_or(false),
+            minimized: window.is_minimized().unwrap_or(false),
+            fullscreen: window.is_fullscreen().unwrap_or(false),
+        }
+    }
 }
 
 unsafe impl Send for EguiWindow {}
@@ -294,83 +657,46 @@ unsafe impl Sync for EguiWindow {}
 impl EguiWindow {
     fn handle_event(&mut self, event: &TaoWindowEvent) -> bool {
         match event {
-            TaoWindowEvent::CursorMoved { position, .. } => {
-                let pos = egui::Pos2::new(
-                    position.x as f32 / self.scale_factor,
-                    position.y as f32 / self.scale_factor,
-                );
-                self.pointer_pos = Some(pos);
-                self.egui_input.events.push(egui::Event::PointerMoved(pos));
-                true
-            }
-            TaoWindowEvent::ModifiersChanged(modifiers) => {
-                self.modifiers = egui::Modifiers {
-                    alt: modifiers.alt_key(),
-                    ctrl: modifiers.control_key(),
-                    shift: modifiers.shift_key(),
-                    #[cfg(target_os = "macos")]
-                    mac_cmd: modifiers.super_key(),
-                    #[cfg(target_os = "macos")]
-                    command: modifiers.super_key(),
-                    #[cfg(not(target_os = "macos"))]
-                    mac_cmd: false,
-                    #[cfg(not(target_os = "macos"))]
-                    command: modifiers.control_key(),
-                };
-                self.egui_input.modifiers = self.modifiers;
-                true
-            }
-            TaoWindowEvent::MouseInput { state, button, .. } => {
-                let pressed = *state == ElementState::Pressed;
-                let button = match button {
-                    MouseButton::Left => egui::PointerButton::Primary,
-                    MouseButton::Right => egui::PointerButton::Secondary,
-                    MouseButton::Middle => egui::PointerButton::Middle,
-                    _ => return false,
-                };
-
-                // Use current pointer position, or default to (0,0) if not set
-                let pos = self.pointer_pos.unwrap_or(egui::Pos2::ZERO);
-
-                self.egui_input.events.push(egui::Event::PointerButton {
-                    pos,
-                    button,
-                    pressed,
-                    modifiers: self.modifiers,
-                });
-                true
-            }
-            TaoWindowEvent::MouseWheel { delta, .. } => {
-                let (x, y) = match delta {
-                    MouseScrollDelta::LineDelta(x, y) => (*x * 60.0, *y * 60.0),
-                    MouseScrollDelta::PixelDelta(pos) => (
-                        pos.x as f32 / self.scale_factor,
-                        pos.y as f32 / self.scale_factor,
-                    ),
-                    _ => (0.0, 0.0),
-                };
-                self.egui_input.events.push(egui::Event::MouseWheel {
-                    unit: egui::MouseWheelUnit::Point,
-                    delta: egui::Vec2::new(x, y),
-                    modifiers: self.modifiers,
-                });
-                true
+            TaoWindowEvent::Focused(_) => {
+                // not every platform resizes minimized windows, but restoring one focuses it
+                self.window_state = WindowState::of(&self.window);
+                self.input.handle_event(event)
             }
             TaoWindowEvent::KeyboardInput { event, .. } => self.handle_keyboard_event(event),
             TaoWindowEvent::ReceivedImeText(txt) => {
-                self.egui_input.events.push(egui::Event::Text(txt.to_string()));
+                if txt.is_empty() {
+                    return false;
+                }
+                // tao only reports the committed text, not the preedit or when the IME
+                // turns on and off, so every commit is a composition of its own.
+                // egui's TextEdit only accepts a commit after the IME was enabled.
+                self.input.egui_input.events.extend([
+                    egui::Event::Ime(egui::ImeEvent::Enabled),
+                    egui::Event::Ime(egui::ImeEvent::Commit(txt.to_string())),
+                    egui::Event::Ime(egui::ImeEvent::Disabled),
+                ]);
                 true
             }
             TaoWindowEvent::Moved(phy_pos) => {
                 false
             }
             TaoWindowEvent::ScaleFactorChanged { scale_factor, .. } => {
-                self.scale_factor = *scale_factor as f32;
-                self.context.set_pixels_per_point(self.scale_factor);
-                self.context.request_repaint();
+                // egui picks up the new scale through `native_pixels_per_point`
+                self.input.scale_factor = *scale_factor as f32;
+                self.request_repaint();
                 false 
             }
-            _ => false,
+            TaoWindowEvent::ThemeChanged(theme) => {
+                self.system_theme = Some(match theme {
+                    TaoTheme::Dark => egui::Theme::Dark,
+                    _ => egui::Theme::Light,
+                });
+                // only visible with `ThemePreference::System`, the default
+                self.request_repaint();
+                // Tauri updates the theme of webviews too
+                false
+            }
+            _ => self.input.handle_event(event),
         }
     }
 
@@ -378,6 +704,14 @@ impl EguiWindow {
         let pressed = event.state == ElementState::Pressed;
         let mut handled = false;
 
+        // Clipboard shortcuts replace the key event, like in egui-winit
+        if pressed {
+            if let Some(clipboard_event) = self.clipboard_event(event) {
+                self.input.egui_input.events.push(clipboard_event);
+                return true;
+            }
+        }
+
         // Handle text input from the text field
         if pressed {
             if let Some(text) = &event.text {
@@ -389,7 +723,7 @@ impl EguiWindow {
                         .collect();
 
                     if !filtered.is_empty() {
-                        self.egui_input.events.push(egui::Event::Text(filtered));
+                        self.input.egui_input.events.push(egui::Event::Text(filtered));
                         handled = true;
                     }
                 }
@@ -397,200 +731,280 @@ impl EguiWindow {
         }
 
         // Handle key events (logical key first, then physical key fallback)
-        if let Some(key) = translate_logical_key(&event.logical_key) {
-            self.egui_input.events.push(egui::Event::Key {
-                key,
-                physical_key: None,
-                pressed,
-                repeat: event.repeat,
-                modifiers: self.modifiers,
-            });
-            handled = true;
-        } else if let Some(key) = translate_physical_key(&event.physical_key) {
-            self.egui_input.events.push(egui::Event::Key {
-                key,
-                physical_key: None,
-                pressed,
-                repeat: event.repeat,
-                modifiers: self.modifiers,
-            });
+        if let Some(key_event) = translate_key_event(
+            &event.logical_key,
+            &event.physical_key,
+            pressed,
+            event.repeat,
+            self.input.modifiers,
+        ) {
+            self.input.egui_input.events.push(key_event);
             handled = true;
         }
 
         handled
     }
 
+    /// Translates copy, cut and paste shortcuts into the matching egui event.
+    fn clipboard_event(&mut self, event: &KeyEvent) -> Option<egui::Event> {
+        let key = translate_logical_key(&event.logical_key)
+            .or_else(|| translate_physical_key(&event.physical_key));
+        let modifiers = self.input.modifiers;
+        let is_key = |k: egui::Key| key == Some(k);
+
+        let is_cut = matches!(event.logical_key, Key::Cut)
+            || (modifiers.command && is_key(egui::Key::X))
+            || (cfg!(target_os = "windows") && modifiers.shift && is_key(egui::Key::Delete));
+        let is_copy = matches!(event.logical_key, Key::Copy)
+            || (modifiers.command && is_key(egui::Key::C))
+            || (cfg!(target_os = "windows") && modifiers.ctrl && is_key(egui::Key::Insert));
+        let is_paste = matches!(event.logical_key, Key::Paste)
+            || (modifiers.command && is_key(egui::Key::V))
+            || (cfg!(target_os = "windows") && modifiers.shift && is_key(egui::Key::Insert));
+
+        if is_cut {
+            Some(egui::Event::Cut)
+        } else if is_copy {
+            Some(egui::Event::Copy)
+        } else if is_paste {
+            self.paste_event()
+        } else {
+            None
+        }
+    }
+
+    /// The egui event pasting the text in the clipboard, if any.
+    fn paste_event(&self) -> Option<egui::Event> {
+        let text = self.clipboard.lock().unwrap().get_text()?;
+        let text = text.replace("\r\n", "\n");
+        (!text.is_empty()).then_some(egui::Event::Paste(text))
+    }
+
+    /// Lets egui drive its Tauri window, e.g. for custom title bars or fullscreen toggles.
+    fn handle_viewport_commands(
+        &mut self,
+        commands: &[egui::ViewportCommand],
+        pixels_per_point: f32,
+        window_id: tauri_runtime::window::WindowId,
+        proxy: &EventLoopProxy<Message<impl UserEvent>>,
+    ) {
+        for command in commands {
+            // these act as if the user pressed the shortcut, in the next frame
+            let clipboard_event = match command {
+                egui::ViewportCommand::RequestCut => Some(egui::Event::Cut),
+                egui::ViewportCommand::RequestCopy => Some(egui::Event::Copy),
+                egui::ViewportCommand::RequestPaste => self.paste_event(),
+                _ => None,
+            };
+            if let Some(event) = clipboard_event {
+                self.input.egui_input.events.push(event);
+                self.request_repaint();
+                continue;
+            }
+            // egui leaves closing other viewports to the app, see `request_close`
+            if *command == egui::ViewportCommand::Close && self.viewport.id != egui::ViewportId::ROOT {
+                self.request_close();
+                continue;
+            }
+
+            for message in translate_viewport_command(command.clone(), pixels_per_point) {
+                if let Err(e) = proxy.send_event(Message::Window(window_id, message)) {
+                    warn!("Failed to send viewport command: {}", e);
+                }
+            }
+        }
+    }
+
     fn handle_platform_output(
         &mut self,
         platform_output: &egui::PlatformOutput,
         window_id: tauri_runtime::window::WindowId,
         proxy: &EventLoopProxy<Message<impl UserEvent>>,
-    ) -> Result<(), Error> {
-        // Handle cursor changes
+    ) {
+        // Handle cursor changes, the window keeps its cursor until told otherwise
         let cursor_icon = platform_output.cursor_icon;
-        let tauri_cursor = egui_cursor_to_tauri_cursor(cursor_icon);
+        if self.cursor_icon != Some(cursor_icon) {
+            let was_hidden = self.cursor_icon == Some(egui::CursorIcon::None);
+            self.cursor_icon = Some(cursor_icon);
 
-        if let Err(e) = proxy.send_event(Message::Window(
-            window_id,
-            WindowMessage::SetCursorIcon(tauri_cursor),
-        )) {
-            eprintln!("Failed to send cursor message: {}", e);
+            let messages = match egui_cursor_to_tauri_cursor(cursor_icon) {
+                None => vec![WindowMessage::SetCursorVisible(false)],
+                Some(icon) if was_hidden => vec![
+                    WindowMessage::SetCursorVisible(true),
+                    WindowMessage::SetCursorIcon(icon),
+                ],
+                Some(icon) => vec![WindowMessage::SetCursorIcon(icon)],
+            };
+            for message in messages {
+                if let Err(e) = proxy.send_event(Message::Window(window_id, message)) {
+                    warn!("Failed to send cursor message: {}", e);
+                }
+            }
         }
 
-        // Handle commands (clipboard, URL opening, etc.)
+        // Handle commands (clipboard, URL opening, etc.), a failing one doesn't stop the others
         for command in &platform_output.commands {
-            match command {
+            let result = match command {
                 egui::output::OutputCommand::CopyText(text) => {
-                    // TODO: Set clipboard content
-                    // For now, just print to console as a placeholder
-                    println!("Clipboard copy text requested: {}", text);
+                    self.clipboard.lock().unwrap().set_text(text)
                 }
                 egui::output::OutputCommand::CopyImage(image) => {
-                    // TODO: Set clipboard image content
-                    // For now, just print to console as a placeholder
-                    println!(
-                        "Clipboard copy image requested: {}x{}",
-                        image.width(),
-                        image.height()
-                    );
+                    self.clipboard.lock().unwrap().set_image(image)
                 }
                 egui::output::OutputCommand::OpenUrl(url) => {
-                    // TODO: Open URL in default browser
-                    // For now, just print to console as a placeholder
-                    println!(
-                        "URL open requested: {} (target: {:?})",
-                        url.url, url.new_tab
-                    );
+                    if (self.url_filter)(&url.url) {
+                        self.url_opener.open_url(&url.url, url.new_tab)
+                    } else {
+                        warn!("URL blocked by the url filter: {}", url.url);
+                        Ok(())
+                    }
                 }
+            };
+            if let Err(e) = result {
+                error!("Error handling platform output: {}", e);
             }
         }
 
-        // Handle IME (Input Method Editor) positioning
-        if let Some(ime_pos) = platform_output.ime {
-            // TODO: Set IME position
-            // For now, just print to console as a placeholder
-            println!("IME position requested: {:?}", ime_pos);
+        // IME (Input Method Editor) positioning: `platform_output.ime` holds the caret rect,
+        // but the Tauri runtime has no window message to forward it to tao's `set_ime_position`,
+        // so the candidate window stays where the platform puts it.
+    }
+
+    /// Shows the default cursor of the window again, in case egui changed or hid it.
+    fn reset_cursor(&self) {
+        match self.cursor_icon {
+            None | Some(egui::CursorIcon::Default) => return,
+            Some(egui::CursorIcon::None) => {
+                if let Err(e) = self.window.set_cursor_visible(true) {
+                    warn!("Failed to show the cursor: {}", e);
+                }
+            }
+            Some(_) => {}
+        }
+        if let Err(e) = self.window.set_cursor_icon(CursorIcon::Default) {
+            warn!("Failed to reset the cursor: {}", e);
         }
+    }
 
-        Ok(())
+    /// Tells egui the user wants to close the viewport, which the app does by no longer showing it.
+    fn request_close(&mut self) {
+        self.input
+            .egui_input
+            .viewports
+            .entry(self.viewport.id)
+            .or_default()
+            .events
+            .push(egui::ViewportEvent::Close);
+        // the parent decides whether to keep showing it
+        self.request_repaint();
+        self.context.request_repaint_of(self.viewport.parent);
+    }
+
+    /// Requests a repaint of the viewport shown in the window.
+    fn request_repaint(&self) {
+        self.context.request_repaint_of(self.viewport.id);
+    }
+
+    /// Applies the output of a pass of the context to the viewport shown in the window.
+    ///
+    /// Any pass outputs all viewports, the commands of each are only in one of them.
+    fn update_viewport(
+        &mut self,
+        output: &egui::ViewportOutput,
+        proxy: &EventLoopProxy<Message<impl UserEvent>>,
+    ) {
+        // the UI of deferred viewports can change every time their parent runs
+        if let Some(viewport_ui_cb) = output.viewport_ui_cb.clone() {
+            self.ui_fn = Some(Box::new(move |ctx| viewport_ui_cb(ctx)));
+        }
+
+        // e.g. a new title, given to the builder rather than as a command
+        let (mut commands, _recreate) = self.viewport.builder.patch(output.builder.clone());
+        commands.extend(output.commands.iter().cloned());
+        if commands.is_empty() {
+            return;
+        }
+        let Some(win_id) = self.win_id else {
+            debug!("Dropping viewport commands for a window not picked up yet: {:?}", commands);
+            return;
+        };
+        let pixels_per_point = self.input.pixels_per_point();
+        self.handle_viewport_commands(&commands, pixels_per_point, win_id, proxy);
+    }
+
+    /// Removes the window from the windows showing the viewports of its context.
+    ///
+    /// Once the root viewport is gone, so are the windows of the other viewports.
+    fn close_viewports(&self, app: &AppHandle) {
+        self.repaint_targets
+            .lock()
+            .unwrap()
+            .remove(&self.viewport.id);
+        if self.viewport.id == egui::ViewportId::ROOT {
+            destroy_viewport_windows(take_viewport_windows(app, &self.context, |_| false));
+        }
+    }
+
+    /// Tessellates and renders the shapes of a pass.
+    fn paint(
+        &mut self,
+        shapes: Vec<egui::epaint::ClippedShape>,
+        pixels_per_point: f32,
+        textures_delta: egui::TexturesDelta,
+    ) -> Result<(), wgpu::SurfaceError> {
+        // Converts all the shapes into triangles meshes
+        let paint_jobs = self.context.tessellate(shapes, pixels_per_point);
+
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [self.size.width, self.size.height],
+            pixels_per_point,
+        };
+        self.renderer
+            .render_frame(screen_descriptor, paint_jobs, textures_delta)
+    }
+
+    /// Records a fatal render error, returning it to be reported unless it already was.
+    ///
+    /// Failing windows keep failing, so this reports the first error rather than every frame.
+    fn fail_render(&mut self, e: wgpu::SurfaceError) -> Option<wgpu::SurfaceError> {
+        let reported = std::mem::replace(&mut self.render_failed, true);
+        (!reported).then_some(e)
+    }
+
+    /// Whether the window is minimized or has no area to render to.
+    ///
+    /// tao doesn't report occlusion, so covered windows still render.
+    fn is_hidden(&self) -> bool {
+        self.window_state.minimized || self.size.width == 0 || self.size.height == 0
     }
 
     fn take_egui_input(&mut self) -> egui::RawInput {
-        let mut input = std::mem::take(&mut self.egui_input);
+        let mut input = std::mem::take(&mut self.input.egui_input);
+        input.viewport_id = self.viewport.id;
         input.time = Some(self.start_time.elapsed().as_secs_f64());
-        input.screen_rect = Some(egui::Rect::from_min_size(
-            egui::Pos2::ZERO,
-            egui::Vec2::new(
-                self.size.width as f32 / self.scale_factor,
-                self.size.height as f32 / self.scale_factor,
-            ),
-        ));
+        input.focused = self.input.focused;
+        input.system_theme = self.system_theme;
+        // egui lays out at the zoom factor on top of `native_pixels_per_point`
+        input.screen_rect = Some(self.input.screen_rect(self.size));
         let max_texture_side = wgpu::Limits::default().max_texture_dimension_2d as usize;
         input.max_texture_side = Some(max_texture_side);
+
+        let viewport = input.viewports.entry(self.viewport.id).or_default();
+        viewport.native_pixels_per_point = Some(self.input.scale_factor);
+        viewport.inner_rect = input.screen_rect;
+        viewport.focused = Some(self.input.focused);
+        viewport.maximized = Some(self.window_state.maximized);
+        viewport.minimized = Some(self.window_state.minimized);
+        viewport.fullscreen = Some(self.window_state.fullscreen);
         input
     }
 }
 
-fn translate_logical_key(key: &Key) -> Option<egui::Key> {
-    match key {
-        Key::Character(ch) => {
-            let ch = ch.chars().next()?;
-            match ch {
-                'a'..='z' | 'A'..='Z' => {
-                    let key_char = ch.to_ascii_uppercase();
-                    match key_char {
-                        'A' => Some(egui::Key::A),
-                        'B' => Some(egui::Key::B),
-                        'C' => Some(egui::Key::C),
-                        'D' => Some(egui::Key::D),
-                        'E' => Some(egui::Key::E),
-                        'F' => Some(egui::Key::F),
-                        'G' => Some(egui::Key::G),
-                        'H' => Some(egui::Key::H),
-                        'I' => Some(egui::Key::I),
-                        'J' => Some(egui::Key::J),
-                        'K' => Some(egui::Key::K),
-                        'L' => Some(egui::Key::L),
-                        'M' => Some(egui::Key::M),
-                        'N' => Some(egui::Key::N),
-                        'O' => Some(egui::Key::O),
-                        'P' => Some(egui::Key::P),
-                        'Q' => Some(egui::Key::Q),
-                        'R' => Some(egui::Key::R),
-                        'S' => Some(egui::Key::S),
-                        'T' => Some(egui::Key::T),
-                        'U' => Some(egui::Key::U),
-                        'V' => Some(egui::Key::V),
-                        'W' => Some(egui::Key::W),
-                        'X' => Some(egui::Key::X),
-                        'Y' => Some(egui::Key::Y),
-                        'Z' => Some(egui::Key::Z),
-                        _ => None,
-                    }
-                }
-                '0'..='9' => match ch {
-                    '0' => Some(egui::Key::Num0),
-                    '1' => Some(egui::Key::Num1),
-                    '2' => Some(egui::Key::Num2),
-                    '3' => Some(egui::Key::Num3),
-                    '4' => Some(egui::Key::Num4),
-                    '5' => Some(egui::Key::Num5),
-                    '6' => Some(egui::Key::Num6),
-                    '7' => Some(egui::Key::Num7),
-                    '8' => Some(egui::Key::Num8),
-                    '9' => Some(egui::Key::Num9),
-                    _ => None,
-                },
-                ' ' => Some(egui::Key::Space),
-                '\t' => Some(egui::Key::Tab),
-                '\n' | '\r' => Some(egui::Key::Enter),
-                '\x08' => Some(egui::Key::Backspace),
-                '\x7f' => Some(egui::Key::Delete),
-                '\x1b' => Some(egui::Key::Escape),
-                _ => None,
-            }
-        }
-        _ => None,
-    }
-}
-
-fn translate_physical_key(key: &KeyCode) -> Option<egui::Key> {
-    match key {
-        KeyCode::ArrowDown => Some(egui::Key::ArrowDown),
-        KeyCode::ArrowLeft => Some(egui::Key::ArrowLeft),
-        KeyCode::ArrowRight => Some(egui::Key::ArrowRight),
-        KeyCode::ArrowUp => Some(egui::Key::ArrowUp),
-        KeyCode::Escape => Some(egui::Key::Escape),
-        KeyCode::Tab => Some(egui::Key::Tab),
-        KeyCode::Backspace => Some(egui::Key::Backspace),
-        KeyCode::Delete => Some(egui::Key::Delete),
-        KeyCode::Enter => Some(egui::Key::Enter),
-        KeyCode::Space => Some(egui::Key::Space),
-        KeyCode::Insert => Some(egui::Key::Insert),
-        KeyCode::Home => Some(egui::Key::Home),
-        KeyCode::End => Some(egui::Key::End),
-        KeyCode::PageUp => Some(egui::Key::PageUp),
-        KeyCode::PageDown => Some(egui::Key::PageDown),
-        KeyCode::F1 => Some(egui::Key::F1),
-        KeyCode::F2 => Some(egui::Key::F2),
-        KeyCode::F3 => Some(egui::Key::F3),
-        KeyCode::F4 => Some(egui::Key::F4),
-        KeyCode::F5 => Some(egui::Key::F5),
-        KeyCode::F6 => Some(egui::Key::F6),
-        KeyCode::F7 => Some(egui::Key::F7),
-        KeyCode::F8 => Some(egui::Key::F8),
-        KeyCode::F9 => Some(egui::Key::F9),
-        KeyCode::F10 => Some(egui::Key::F10),
-        KeyCode::F11 => Some(egui::Key::F11),
-        KeyCode::F12 => Some(egui::Key::F12),
-        _ => None,
-    }
-}
-
-fn egui_cursor_to_tauri_cursor(egui_cursor: egui::CursorIcon) -> CursorIcon {
-    match egui_cursor {
+/// Returns `None` for [`egui::CursorIcon::None`], which hides the cursor instead.
+fn egui_cursor_to_tauri_cursor(egui_cursor: egui::CursorIcon) -> Option<CursorIcon> {
+    Some(match egui_cursor {
+        egui::CursorIcon::None => return None,
         egui::CursorIcon::Default => CursorIcon::Default,
-        egui::CursorIcon::None => CursorIcon::Default, // No equivalent, use default
         egui::CursorIcon::ContextMenu => CursorIcon::ContextMenu,
         egui::CursorIcon::Help => CursorIcon::Help,
         egui::CursorIcon::PointingHand => CursorIcon::Hand,
@@ -612,20 +1026,116 @@ fn egui_cursor_to_tauri_cursor(egui_cursor: egui::CursorIcon) -> CursorIcon {
         egui::CursorIcon::ResizeNeSw => CursorIcon::NeswResize,
         egui::CursorIcon::ResizeNwSe => CursorIcon::NwseResize,
         egui::CursorIcon::ResizeVertical => CursorIcon::NsResize,
+        egui::CursorIcon::ResizeEast => CursorIcon::EResize,
+        egui::CursorIcon::ResizeSouthEast => CursorIcon::SeResize,
+        egui::CursorIcon::ResizeSouth => CursorIcon::SResize,
+        egui::CursorIcon::ResizeSouthWest => CursorIcon::SwResize,
+        egui::CursorIcon::ResizeWest => CursorIcon::WResize,
+        egui::CursorIcon::ResizeNorthWest => CursorIcon::NwResize,
+        egui::CursorIcon::ResizeNorth => CursorIcon::NResize,
+        egui::CursorIcon::ResizeNorthEast => CursorIcon::NeResize,
+        egui::CursorIcon::ResizeColumn => CursorIcon::ColResize,
+        egui::CursorIcon::ResizeRow => CursorIcon::RowResize,
         egui::CursorIcon::ZoomIn => CursorIcon::ZoomIn,
         egui::CursorIcon::ZoomOut => CursorIcon::ZoomOut,
-        // Fallback for any other variants
-        _ => CursorIcon::Default,
-    }
+    })
 }
 
 pub trait AppHandleExt {
+    /// Starts rendering egui in a window, running `ui_fn` every frame.
+    ///
+    /// Text typed through an IME reaches egui once committed, as an `Enabled`, `Commit`
+    /// and `Disabled` IME event. Composition (preedit) text and the position of the candidate
+    /// window aren't supported: tao only reports committed text, and the Tauri runtime
+    /// can't forward `PlatformOutput::ime` to the window.
     fn start_egui_for_window(
         &self,
         label: &str,
         ui_fn: Box<dyn FnMut(&egui::Context)>,
         on_destroy: Option<Box<dyn FnMut(String)>>,
     ) -> Result<(), Error>;
+
+    /// Like [`AppHandleExt::start_egui_for_window`], but renders the window with `options`
+    /// instead of the ones set with [`Builder::renderer_options`].
+    fn start_egui_for_window_with_options(
+        &self,
+        label: &str,
+        ui_fn: Box<dyn FnMut(&egui::Context)>,
+        on_destroy: Option<Box<dyn FnMut(String)>>,
+        options: RendererOptions,
+    ) -> Result<(), Error>;
+
+    /// Stops rendering egui in a window, without closing it.
+    ///
+    /// This drops the egui context and GPU surface of the window and runs its `on_destroy`.
+    /// The window gets its default cursor back, in case egui changed or hid it.
+    /// egui can be started again for the window afterwards, e.g. with a different UI.
+    fn stop_egui_for_window(&self, label: &str) -> Result<(), Error>;
+
+    /// Replaces the UI closure of a window egui is running in, keeping its context and GPU surface.
+    fn set_egui_ui(&self, label: &str, ui_fn: Box<dyn FnMut(&egui::Context)>) -> Result<(), Error>;
+
+    /// Gets the egui context of a window egui is running in.
+    fn get_egui_context(&self, label: &str) -> Option<egui::Context>;
+
+    /// Sets how fast the mouse wheel scrolls in a window egui is running in, `1.0` by default.
+    ///
+    /// The multiplier must be finite and above zero, otherwise
+    /// [`Error::InvalidScrollSpeed`] is returned.
+    fn set_egui_scroll_speed(&self, label: &str, multiplier: f32) -> Result<(), Error>;
+
+    /// Sets whether a window egui is running in follows the system theme, or uses a light or dark one.
+    ///
+    /// The theme applies to the windows of the viewports shown from the window too.
+    /// The webview can do the same by emitting an `egui_theme` event,
+    /// e.g. with `{ "label": "main", "theme": "dark" }` as payload.
+    fn set_egui_theme(&self, label: &str, theme: egui::ThemePreference) -> Result<(), Error>;
+
+    /// The GPU device shared by all egui windows, and the egui renderer of a window.
+    ///
+    /// Textures of the app registered with `renderer.write().register_native_texture(..)`
+    /// can be shown in the window, and the windows of its viewports, with their `TextureId`.
+    /// Textures created on the `device` can be registered with the renderer of any window.
+    fn egui_render_state(&self, label: &str) -> Result<egui_wgpu::RenderState, Error>;
+
+    /// Information about the GPU adapter a window is rendered with,
+    /// e.g. to tell whether a software adapter is used.
+    fn adapter_info(&self, label: &str) -> Result<wgpu::AdapterInfo, Error>;
+
+    /// Requests a redraw of a window egui is running in, from any thread.
+    ///
+    /// The webview can do the same by emitting an `egui_redraw` event with
+    /// `{ "label": "main" }` or just the window label as payload,
+    /// or without payload to redraw all egui windows.
+    fn request_egui_redraw(&self, label: &str) -> Result<(), Error>;
+
+    /// Requests a redraw of all windows egui is running in.
+    fn request_egui_redraw_all(&self) -> Result<(), Error>;
+}
+
+/// Runs `f` with the egui window of `label`, whether it is still staged or already live.
+fn with_egui_window<R>(
+    app: &AppHandle,
+    label: &str,
+    f: impl FnOnce(&mut EguiWindow) -> R,
+) -> Result<R, Error> {
+    let staging_window = app
+        .try_state::<StagingWindow>()
+        .ok_or(Error::PluginNotInitialized)?;
+    let egui_windows = app
+        .try_state::<EguiWindowMap>()
+        .ok_or(Error::PluginNotInitialized)?;
+
+    // locked in the same order as the event loop: the live windows, then the staged ones
+    let mut egui_windows = egui_windows.lock().unwrap();
+    let mut staging_window = staging_window.lock().unwrap();
+    let egui_window = match staging_window.windows.get_mut(label) {
+        Some(egui_window) => egui_window,
+        None => egui_windows
+            .get_mut(label)
+            .ok_or_else(|| Error::NotAttached(label.to_string()))?,
+    };
+    Ok(f(egui_window))
 }
 
 impl AppHandleExt for AppHandle {
@@ -634,49 +1144,517 @@ impl AppHandleExt for AppHandle {
         label: &str,
         ui_fn: Box<dyn FnMut(&egui::Context)>,
         on_destroy: Option<Box<dyn FnMut(String)>>,
+    ) -> Result<(), Error> {
+        let options = *self
+            .try_state::<RendererOptions>()
+            .ok_or(Error::PluginNotInitialized)?
+            .inner();
+        self.start_egui_for_window_with_options(label, ui_fn, on_destroy, options)
+    }
+
+    fn start_egui_for_window_with_options(
+        &self,
+        label: &str,
+        ui_fn: Box<dyn FnMut(&egui::Context)>,
+        on_destroy: Option<Box<dyn FnMut(String)>>,
+        options: RendererOptions,
     ) -> Result<(), Error> {
         // check if window exists
         let window = self
             .get_window(label)
-            .ok_or(Error::msg("No Window found with the provided label."))?;
+            .ok_or_else(|| Error::WindowNotFound(label.to_string()))?;
 
-        // extract relevant window details
-        let scale_factor = window.scale_factor().unwrap_or(1.0) as f32;
-        let size = window.inner_size()?;
-        let PhysicalSize { width, height } = size;
-
-        // create egui context + renderer
+        // other viewports are shown in Tauri windows of their own, see `sync_viewport_windows`
         let context = egui::Context::default();
-        context.set_zoom_factor(scale_factor);
-        let renderer =
-            tauri::async_runtime::block_on(
-                async move { Renderer::new(window, width, height).await },
-            )?;
-
-        // check if plugin is init'd
-        let staging_window= self
+        context.set_embed_viewports(false);
+        let shared = SharedContext {
+            context,
+            repaint_targets: Arc::new(Mutex::new(HashMap::new())),
+            start_time: Instant::now(),
+            egui_renderer: None,
+        };
+
+        attach_egui_window(self, window, shared, Viewport::root(), Some(ui_fn), on_destroy, options)
+    }
+    fn stop_egui_for_window(&self, label: &str) -> Result<(), Error> {
+        let staging_window = self
             .try_state::<StagingWindow>()
-            .ok_or(Error::msg("TauriPluginEgui is not initialized"))?;
-
-        // track in the plugin state
-        let mut stage_window = staging_window.lock().unwrap();
-        stage_window.window = Some((
-            label.to_string(),
-            EguiWindow {
-                label: label.to_string(),
-                context,
-                renderer,
-                ui_fn,
-                on_destroy,
-                size,
-                start_time: Instant::now(),
-                egui_input: egui::RawInput::default(),
-                pointer_pos: None,
-                scale_factor,
-                modifiers: egui::Modifiers::NONE,
-            },
-        ));
+            .ok_or(Error::PluginNotInitialized)?;
+        let egui_windows = self
+            .try_state::<EguiWindowMap>()
+            .ok_or(Error::PluginNotInitialized)?;
+
+        let egui_window = staging_window.lock().unwrap().windows.remove(label);
+        let mut egui_window = egui_window
+            .or_else(|| egui_windows.lock().unwrap().remove(label))
+            .ok_or_else(|| Error::NotAttached(label.to_string()))?;
+
+        debug!("egui stopped for window {}", label);
+        // the window stays open, without egui to set its cursor anymore
+        egui_window.reset_cursor();
+        egui_window.close_viewports(self);
+        // the locks are released, so `on_destroy` may call back into the plugin
+        if let Some(mut on_destroy) = egui_window.on_destroy.take() {
+            on_destroy(label.to_string());
+        }
 
         Ok(())
     }
+    fn set_egui_ui(&self, label: &str, ui_fn: Box<dyn FnMut(&egui::Context)>) -> Result<(), Error> {
+        // if the UI is running right now, this is picked up once it returns
+        with_egui_window(self, label, |egui_window| egui_window.ui_fn = Some(ui_fn))?;
+        self.request_egui_redraw(label)
+    }
+
+    fn get_egui_context(&self, label: &str) -> Option<egui::Context> {
+        with_egui_window(self, label, |egui_window| egui_window.context.clone()).ok()
+    }
+
+    fn set_egui_scroll_speed(&self, label: &str, multiplier: f32) -> Result<(), Error> {
+        // zero stops scrolling, negative values invert it, NaN breaks it
+        if !(multiplier.is_finite() && multiplier > 0.0) {
+            return Err(Error::InvalidScrollSpeed(multiplier));
+        }
+        with_egui_window(self, label, |egui_window| egui_window.input.scroll_speed = multiplier)
+    }
+
+    fn set_egui_theme(&self, label: &str, theme: egui::ThemePreference) -> Result<(), Error> {
+        let (context, viewport_id) = with_egui_window(self, label, |egui_window| {
+            (egui_window.context.clone(), egui_window.viewport.id)
+        })?;
+        context.set_theme(theme);
+        context.request_repaint_of(viewport_id);
+        Ok(())
+    }
+
+    fn egui_render_state(&self, label: &str) -> Result<egui_wgpu::RenderState, Error> {
+        with_egui_window(self, label, |egui_window| egui_window.renderer.render_state())
+    }
+
+    fn adapter_info(&self, label: &str) -> Result<wgpu::AdapterInfo, Error> {
+        with_egui_window(self, label, |egui_window| egui_window.renderer.adapter_info())
+    }
+    fn request_egui_redraw(&self, label: &str) -> Result<(), Error> {
+        let (context, viewport_id) = with_egui_window(self, label, |egui_window| {
+            (egui_window.context.clone(), egui_window.viewport.id)
+        })?;
+        context.request_repaint_of(viewport_id);
+        Ok(())
+    }
+
+    fn request_egui_redraw_all(&self) -> Result<(), Error> {
+        let staging_window = self
+            .try_state::<StagingWindow>()
+            .ok_or(Error::PluginNotInitialized)?;
+        let egui_windows = self
+            .try_state::<EguiWindowMap>()
+            .ok_or(Error::PluginNotInitialized)?;
+
+        // repaint outside of the locks, the repaint callback may run right away
+        let viewport = |egui_window: &EguiWindow| (egui_window.context.clone(), egui_window.viewport.id);
+        let mut viewports: Vec<(egui::Context, egui::ViewportId)> = staging_window
+            .lock()
+            .unwrap()
+            .windows
+            .values()
+            .map(viewport)
+            .collect();
+        viewports.extend(egui_windows.lock().unwrap().values().map(viewport));
+        for (context, viewport_id) in viewports {
+            context.request_repaint_of(viewport_id);
+        }
+        Ok(())
+    }
+}
+
+/// The egui context shared by the windows of its viewports, with what they keep in common.
+#[derive(Clone)]
+struct SharedContext {
+    context: egui::Context,
+    repaint_targets: RepaintTargets,
+    // egui expects the same clock in every viewport
+    start_time: Instant,
+    // `None` until the window of the root viewport creates it
+    egui_renderer: Option<EguiRenderer>,
+}
+
+/// Starts egui in a Tauri window, showing `viewport` of the context.
+///
+/// The window is staged until the event loop picks it up.
+fn attach_egui_window(
+    app: &AppHandle,
+    window: tauri::Window,
+    shared: SharedContext,
+    viewport: Viewport,
+    ui_fn: Option<UiFn>,
+    on_destroy: Option<Box<dyn FnMut(String)>>,
+    options: RendererOptions,
+) -> Result<(), Error> {
+    let label = window.label().to_string();
+
+    // extract relevant window details
+    let scale_factor = window.scale_factor().unwrap_or(1.0) as f32;
+    let focused = window.is_focused().unwrap_or(true);
+    let window_state = WindowState::of(&window);
+    let system_theme = window.theme().ok().map(|theme| match theme {
+        tauri::Theme::Dark => egui::Theme::Dark,
+        _ => egui::Theme::Light,
+    });
+    let size = window.inner_size()?;
+    let PhysicalSize { width, height } = size;
+
+    // check if plugin is init'd
+    let staging_window= app
+        .try_state::<StagingWindow>()
+        .ok_or(Error::PluginNotInitialized)?;
+    let egui_windows = app
+        .try_state::<EguiWindowMap>()
+        .ok_or(Error::PluginNotInitialized)?;
+    // locked in the same order as the event loop: the live windows, then the staged ones
+    let is_attached = |label: &str| {
+        let egui_windows = egui_windows.lock().unwrap();
+        egui_windows.contains_key(label) || staging_window.lock().unwrap().windows.contains_key(label)
+    };
+    if is_attached(&label) {
+        return Err(Error::AlreadyAttached(label));
+    }
+    let shared_gpu = app
+        .try_state::<SharedGpu>()
+        .ok_or(Error::PluginNotInitialized)?;
+    let clipboard = app
+        .try_state::<SharedClipboard>()
+        .ok_or(Error::PluginNotInitialized)?
+        .inner()
+        .clone();
+    let url_opener = app
+        .try_state::<SharedUrlOpener>()
+        .ok_or(Error::PluginNotInitialized)?
+        .inner()
+        .clone();
+    let url_filter = app
+        .try_state::<UrlFilter>()
+        .ok_or(Error::PluginNotInitialized)?
+        .inner()
+        .clone();
+
+    // reuse the shared GPU context, or create it for the first window
+    let mut shared_gpu = shared_gpu.lock().unwrap();
+    let (gpu, surface) = match shared_gpu.as_ref() {
+        Some(gpu) => {
+            let surface = gpu.instance.create_surface(window.clone())?;
+            (gpu.clone(), surface)
+        }
+        None => {
+            let gpu_options = app
+                .try_state::<GpuOptions>()
+                .ok_or(Error::PluginNotInitialized)?;
+            // tries a software adapter and the GL backend when no GPU is found
+            let (gpu, surface) = tauri::async_runtime::block_on(Gpu::new_with_fallback(
+                &gpu_options,
+                |instance| Ok(Some(instance.create_surface(window.clone())?)),
+            ))?;
+            let surface = surface.expect("a surface is created with every instance");
+            let gpu = Arc::new(gpu);
+            *shared_gpu = Some(gpu.clone());
+            (gpu, surface)
+        }
+    };
+    drop(shared_gpu);
+
+    // windows of other viewports draw with the egui renderer of their context
+    let renderer = Renderer::new(gpu, surface, width, height, &options, shared.egui_renderer)?;
+
+    // track in the plugin state, unless the same label was registered in the meantime,
+    // checked and inserted under the same locks
+    let egui_windows = egui_windows.lock().unwrap();
+    let mut staging_window = staging_window.lock().unwrap();
+    if egui_windows.contains_key(&label) {
+        return Err(Error::AlreadyAttached(label));
+    }
+    let Entry::Vacant(entry) = staging_window.windows.entry(label.clone()) else {
+        return Err(Error::AlreadyAttached(label));
+    };
+    let input = WindowInput::new(shared.context.clone(), scale_factor, focused);
+    entry.insert(
+        EguiWindow {
+            label,
+            window,
+            context: shared.context,
+            renderer,
+            ui_fn,
+            on_destroy,
+            clipboard,
+            url_opener,
+            url_filter,
+            size,
+            start_time: shared.start_time,
+            input,
+            cursor_icon: None,
+            render_failed: false,
+            window_state,
+            system_theme,
+            win_id: None,
+            viewport,
+            repaint_targets: shared.repaint_targets,
+            renderer_options: options,
+        },
+    );
+
+    Ok(())
+}
+
+/// The label of the window showing the viewport `id` of `context`, whether staged or live.
+fn viewport_window_label(
+    app: &AppHandle,
+    context: &egui::Context,
+    id: egui::ViewportId,
+) -> Option<String> {
+    let staging_window = app.try_state::<StagingWindow>()?;
+    let egui_windows = app.try_state::<EguiWindowMap>()?;
+
+    let find = |windows: &HashMap<String, EguiWindow>| {
+        windows
+            .iter()
+            .find(|(_, egui_window)| egui_window.context == *context && egui_window.viewport.id == id)
+            .map(|(label, _)| label.clone())
+    };
+    let label = find(&staging_window.lock().unwrap().windows);
+    label.or_else(|| find(&egui_windows.lock().unwrap()))
+}
+
+/// Removes the windows of the viewports of `context` that `keep` returns `false` for,
+/// leaving out the root viewport.
+fn take_viewport_windows(
+    app: &AppHandle,
+    context: &egui::Context,
+    keep: impl Fn(egui::ViewportId) -> bool,
+) -> Vec<EguiWindow> {
+    let (Some(staging_window), Some(egui_windows)) =
+        (app.try_state::<StagingWindow>(), app.try_state::<EguiWindowMap>())
+    else {
+        return Vec::new();
+    };
+
+    let take = |windows: &mut HashMap<String, EguiWindow>| {
+        let labels: Vec<String> = windows
+            .iter()
+            .filter(|(_, egui_window)| {
+                let id = egui_window.viewport.id;
+                egui_window.context == *context && id != egui::ViewportId::ROOT && !keep(id)
+            })
+            .map(|(label, _)| label.clone())
+            .collect();
+        labels
+            .iter()
+            .filter_map(|label| windows.remove(label))
+            .collect::<Vec<_>>()
+    };
+    let mut taken = take(&mut staging_window.lock().unwrap().windows);
+    taken.extend(take(&mut egui_windows.lock().unwrap()));
+    taken
+}
+
+/// Destroys the Tauri windows of viewports egui doesn't show anymore.
+fn destroy_viewport_windows(egui_windows: Vec<EguiWindow>) {
+    for egui_window in egui_windows {
+        debug!("Closing the window of viewport {:?}", egui_window.viewport.id);
+        egui_window
+            .repaint_targets
+            .lock()
+            .unwrap()
+            .remove(&egui_window.viewport.id);
+        if let Err(e) = egui_window.window.destroy() {
+            warn!("Failed to close the window of a viewport: {}", e);
+        }
+    }
+}
+
+/// Opens, updates and closes the windows of the deferred viewports of `context`,
+/// after a pass of the viewport shown in the window `label`.
+///
+/// Immediate viewports are opened when they are rendered, see `render_immediate_viewport`.
+fn sync_viewport_windows(
+    app: &AppHandle,
+    label: &str,
+    context: &egui::Context,
+    viewport_output: &egui::OrderedViewportIdMap<egui::ViewportOutput>,
+    proxy: &EventLoopProxy<Message<impl UserEvent>>,
+) {
+    // egui drops the viewports the app stopped showing
+    destroy_viewport_windows(take_viewport_windows(app, context, |id| {
+        viewport_output.contains_key(&id)
+    }));
+
+    for (&id, output) in viewport_output {
+        if let Some(viewport_label) = viewport_window_label(app, context, id) {
+            let _ = with_egui_window(app, &viewport_label, |egui_window| {
+                egui_window.update_viewport(output, proxy)
+            });
+            continue;
+        }
+        let Some(viewport_ui_cb) = output.viewport_ui_cb.clone() else {
+            continue;
+        };
+
+        let viewport = Viewport {
+            id,
+            parent: output.parent,
+            class: output.class,
+            builder: output.builder.clone(),
+        };
+        let ui_fn: UiFn = Box::new(move |ctx| viewport_ui_cb(ctx));
+        if let Err(e) = create_viewport_window(app, label, context, viewport, Some(ui_fn)) {
+            warn!("Failed to open a window for viewport {:?}: {}", id, e);
+        }
+    }
+}
+
+/// Opens a Tauri window for a viewport and starts egui in it, as a sibling of the window `label`.
+///
+/// Returns the label of the new window.
+fn create_viewport_window(
+    app: &AppHandle,
+    label: &str,
+    context: &egui::Context,
+    viewport: Viewport,
+    ui_fn: Option<UiFn>,
+) -> Result<String, Error> {
+    let (shared, options) = with_egui_window(app, label, |egui_window| {
+        let shared = SharedContext {
+            context: egui_window.context.clone(),
+            repaint_targets: egui_window.repaint_targets.clone(),
+            start_time: egui_window.start_time,
+            egui_renderer: Some(egui_window.renderer.egui_renderer().clone()),
+        };
+        (shared, egui_window.renderer_options)
+    })?;
+
+    let viewport_label = viewport_label(label, viewport.id);
+    debug!("Opening window {} for viewport {:?}", viewport_label, viewport.id);
+    let window = window_builder(app, &viewport_label, &viewport.builder, context.zoom_factor())
+        .build()?;
+    attach_egui_window(app, window, shared, viewport, ui_fn, None, options)?;
+    Ok(viewport_label)
+}
+
+/// Runs and renders an immediate viewport in its own window, from within the pass of its parent.
+///
+/// Falls back to embedding the viewport in its parent when it can't get a window.
+fn render_immediate_viewport<T: UserEvent>(
+    app: &AppHandle,
+    proxy: &EventLoopProxy<Message<T>>,
+    on_error: Option<&ErrorHandler>,
+    context: &egui::Context,
+    viewport: egui::ImmediateViewport<'_>,
+) {
+    let egui::ImmediateViewport {
+        ids,
+        builder,
+        mut viewport_ui_cb,
+    } = viewport;
+
+    let label = viewport_window_label(app, context, ids.this).or_else(|| {
+        let parent_label = viewport_window_label(app, context, ids.parent)?;
+        let viewport = Viewport {
+            id: ids.this,
+            parent: ids.parent,
+            class: egui::ViewportClass::Immediate,
+            builder: builder.clone(),
+        };
+        create_viewport_window(app, &parent_label, context, viewport, None)
+            .inspect_err(|e| warn!("Failed to open a window for viewport {:?}: {}", ids.this, e))
+            .ok()
+    });
+    let Some(label) = label else {
+        viewport_ui_cb(context);
+        return;
+    };
+
+    let Ok(raw_input) = with_egui_window(app, &label, |egui_window| {
+        let output = egui::ViewportOutput {
+            parent: ids.parent,
+            class: egui::ViewportClass::Immediate,
+            builder,
+            viewport_ui_cb: None,
+            commands: Vec::new(),
+            repaint_delay: std::time::Duration::MAX,
+        };
+        egui_window.update_viewport(&output, proxy);
+
+        // repainting it means repainting the window of its parent, which runs its UI
+        let mut repaint_targets = egui_window.repaint_targets.lock().unwrap();
+        if let Some(parent_win_id) = repaint_targets.get(&ids.parent).copied() {
+            repaint_targets.insert(ids.this, parent_win_id);
+        }
+        drop(repaint_targets);
+
+        egui_window.take_egui_input()
+    }) else {
+        viewport_ui_cb(context);
+        return;
+    };
+
+    // the window isn't locked, like for deferred viewports
+    let egui::FullOutput {
+        textures_delta,
+        shapes,
+        pixels_per_point,
+        platform_output,
+        ..
+    } = context.run(raw_input, |ctx| viewport_ui_cb(ctx));
+
+    let render_error = with_egui_window(app, &label, |egui_window| {
+        if let Some(win_id) = egui_window.win_id {
+            egui_window.handle_platform_output(&platform_output, win_id, proxy);
+        }
+        if egui_window.is_hidden() {
+            // the fonts and images of the other windows may have changed in this pass
+            egui_window.renderer.update_textures(&textures_delta);
+            return None;
+        }
+        match egui_window.paint(shapes, pixels_per_point, textures_delta) {
+            Ok(()) => {
+                egui_window.render_failed = false;
+                None
+            }
+            // drawn again along with the next frame of its parent
+            Err(
+                wgpu::SurfaceError::Lost
+                | wgpu::SurfaceError::Outdated
+                | wgpu::SurfaceError::Timeout,
+            ) => {
+                egui_window.request_repaint();
+                None
+            }
+            Err(e) => egui_window.fail_render(e),
+        }
+    });
+    if let Ok(Some(e)) = render_error {
+        report_render_error(on_error, &label, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redraw_event_payloads() {
+        let cases = [
+            (r#""main""#, Some(Some("main"))),
+            (r#"{ "label": "main" }"#, Some(Some("main"))),
+            ("null", Some(None)),
+            ("{}", Some(None)),
+            (r#"{ "label": null }"#, Some(None)),
+            // malformed payloads are ignored rather than redrawing every window
+            ("42", None),
+            (r#"{ "label": 42 }"#, None),
+            (r#"["main"]"#, None),
+            ("main", None),
+        ];
+        for (payload, expected) in cases {
+            let label = serde_json::from_str::<RedrawEvent>(payload).ok().map(RedrawEvent::label);
+            assert_eq!(label, expected.map(|label| label.map(str::to_string)), "{payload}");
+        }
+    }
 }
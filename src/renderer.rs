@@ -1,32 +1,175 @@
-use anyhow::Error;
+use std::sync::Arc;
+
+use egui::mutex::RwLock;
 use egui_wgpu::wgpu;
 
+use crate::{Error, GpuOptions, RendererOptions};
+
+/// The egui renderer of an egui context, shared by the windows of all its viewports.
+///
+/// egui hands out the texture updates of a pass once for the whole context,
+/// so its windows have to draw with the same textures, and so the same pipeline.
+#[derive(Clone)]
+pub struct EguiRenderer {
+    pub renderer: Arc<RwLock<egui_wgpu::Renderer>>,
+    pub format: wgpu::TextureFormat,
+    pub msaa_samples: u32,
+}
+
 pub struct Renderer {
-    gpu: Gpu,
-    egui_renderer: egui_wgpu::Renderer,
+    gpu: Arc<Gpu>,
+    surface: WindowSurface,
+    egui_renderer: EguiRenderer,
+    // rendered into and resolved to the surface texture, with MSAA only
+    msaa_view: Option<wgpu::TextureView>,
 }
 
 impl Renderer {
-    pub async fn new(
-        window: impl Into<wgpu::SurfaceTarget<'static>>,
+    /// Creates the renderer of a window, drawing with `egui_renderer` if the window
+    /// shows a viewport of an egui context that already has one.
+    pub fn new(
+        gpu: Arc<Gpu>,
+        surface: wgpu::Surface<'static>,
         width: u32,
         height: u32,
+        options: &RendererOptions,
+        egui_renderer: Option<EguiRenderer>,
     ) -> Result<Self, Error> {
-        let gpu = Gpu::new_async(window, width, height).await?;
+        let required_format = egui_renderer.as_ref().map(|egui_renderer| egui_renderer.format);
+        let surface = WindowSurface::new(&gpu, surface, width, height, options, required_format)?;
 
-        let egui_renderer =
-            egui_wgpu::Renderer::new(
-                &gpu.device,
-                gpu.surface_config.format,
-                egui_wgpu::RendererOptions::default(),
-            );
-            //egui_wgpu::Renderer::new(&gpu.device, gpu.surface_config.format, None, 1, false);
+        let egui_renderer = match egui_renderer {
+            Some(egui_renderer) => egui_renderer,
+            None => Self::create_egui_renderer(&gpu, surface.surface_config.format, options),
+        };
+
+        let msaa_view = Self::create_msaa_view(
+            &gpu.device,
+            &surface.surface_config,
+            egui_renderer.msaa_samples,
+        );
 
-        Ok(Self { gpu, egui_renderer })
+        Ok(Self {
+            gpu,
+            surface,
+            egui_renderer,
+            msaa_view,
+        })
+    }
+
+    fn create_egui_renderer(
+        gpu: &Gpu,
+        format: wgpu::TextureFormat,
+        options: &RendererOptions,
+    ) -> EguiRenderer {
+        // without this feature, wgpu only allows the sample counts every adapter supports
+        let device_features = gpu.device.features();
+        let format_features = if device_features
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            gpu.adapter.get_texture_format_features(format)
+        } else {
+            format.guaranteed_format_features(device_features)
+        };
+        let msaa_samples = options.msaa_samples.max(1);
+        let msaa_samples = if format_features.flags.sample_count_supported(msaa_samples) {
+            msaa_samples
+        } else {
+            warn!("{}x MSAA is not supported for {:?}, turning it off", msaa_samples, format);
+            1
+        };
+
+        let renderer = egui_wgpu::Renderer::new(
+            &gpu.device,
+            format,
+            egui_wgpu::RendererOptions {
+                msaa_samples,
+                ..Default::default()
+            },
+        );
+
+        EguiRenderer {
+            renderer: Arc::new(RwLock::new(renderer)),
+            format,
+            msaa_samples,
+        }
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
-        self.gpu.resize(width, height);
+        self.surface.resize(&self.gpu.device, width, height);
+        self.msaa_view = Self::create_msaa_view(
+            &self.gpu.device,
+            &self.surface.surface_config,
+            self.egui_renderer.msaa_samples,
+        );
+    }
+
+    /// The egui renderer the window draws with, to share with the windows of other viewports.
+    pub fn egui_renderer(&self) -> &EguiRenderer {
+        &self.egui_renderer
+    }
+
+    /// The shared GPU device and the egui renderer of the window,
+    /// e.g. to register a texture of the app with `register_native_texture`.
+    pub fn render_state(&self) -> egui_wgpu::RenderState {
+        egui_wgpu::RenderState {
+            adapter: self.gpu.adapter.clone(),
+            available_adapters: vec![self.gpu.adapter.clone()],
+            device: self.gpu.device.clone(),
+            queue: self.gpu.queue.clone(),
+            target_format: self.egui_renderer.format,
+            renderer: self.egui_renderer.renderer.clone(),
+        }
+    }
+
+    /// Information about the adapter the window is rendered with.
+    pub fn adapter_info(&self) -> wgpu::AdapterInfo {
+        self.gpu.adapter.get_info()
+    }
+
+    fn create_msaa_view(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        msaa_samples: u32,
+    ) -> Option<wgpu::TextureView> {
+        if msaa_samples <= 1 {
+            return None;
+        }
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("MSAA Texture"),
+            size: wgpu::Extent3d {
+                width: config.width.max(1),
+                height: config.height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: msaa_samples,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
+
+    /// Renders a frame to the window surface.
+    ///
+    /// Textures are updated even if the frame cannot be presented.
+    /// On [`wgpu::SurfaceError::Lost`] and [`wgpu::SurfaceError::Outdated`] the surface is
+    /// reconfigured before returning, so the frame can simply be drawn again.
+    /// Uploads and frees the textures of a frame without drawing it.
+    ///
+    /// egui hands out the texture changes of a context once, for all of its viewports,
+    /// so they have to reach the shared egui renderer even when a window isn't drawn.
+    pub fn update_textures(&self, textures_delta: &egui::TexturesDelta) {
+        let mut egui_renderer = self.egui_renderer.renderer.write();
+        for (id, image_delta) in &textures_delta.set {
+            egui_renderer.update_texture(&self.gpu.device, &self.gpu.queue, *id, image_delta);
+        }
+
+        for id in &textures_delta.free {
+            egui_renderer.free_texture(id);
+        }
     }
 
     pub fn render_frame(
@@ -35,15 +178,9 @@ impl Renderer {
         paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
         textures_delta: egui::TexturesDelta,
         // _delta_time: std::time::Duration,
-    ) {
-        for (id, image_delta) in &textures_delta.set {
-            self.egui_renderer
-                .update_texture(&self.gpu.device, &self.gpu.queue, *id, image_delta);
-        }
-
-        for id in &textures_delta.free {
-            self.egui_renderer.free_texture(id);
-        }
+    ) -> Result<(), wgpu::SurfaceError> {
+        self.update_textures(&textures_delta);
+        let mut egui_renderer = self.egui_renderer.renderer.write();
 
         let mut encoder = self
             .gpu
@@ -52,7 +189,7 @@ impl Renderer {
                 label: Some("Render Encoder"),
             });
 
-        self.egui_renderer.update_buffers(
+        egui_renderer.update_buffers(
             &self.gpu.device,
             &self.gpu.queue,
             &mut encoder,
@@ -60,11 +197,14 @@ impl Renderer {
             &screen_descriptor,
         );
 
-        let surface_texture = self
-            .gpu
-            .surface
-            .get_current_texture()
-            .expect("Failed to get surface texture!");
+        let surface_texture = match self.surface.surface.get_current_texture() {
+            Ok(surface_texture) => surface_texture,
+            Err(e @ (wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated)) => {
+                self.surface.reconfigure(&self.gpu.device);
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
 
         let surface_texture_view =
             surface_texture
@@ -72,7 +212,7 @@ impl Renderer {
                 .create_view(&wgpu::TextureViewDescriptor {
                     label: wgpu::Label::default(),
                     aspect: wgpu::TextureAspect::default(),
-                    format: Some(self.gpu.surface_format),
+                    format: Some(self.surface.surface_format),
                     usage: None,
                     dimension: None,
                     base_mip_level: 0,
@@ -87,13 +227,25 @@ impl Renderer {
         {
             let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &surface_texture_view,
-                    resolve_target: None,
-                    depth_slice: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-                        store: wgpu::StoreOp::Store,
+                color_attachments: &[Some(match &self.msaa_view {
+                    Some(msaa_view) => wgpu::RenderPassColorAttachment {
+                        view: msaa_view,
+                        resolve_target: Some(&surface_texture_view),
+                        depth_slice: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            // only the resolved frame is needed
+                            store: wgpu::StoreOp::Discard,
+                        },
+                    },
+                    None => wgpu::RenderPassColorAttachment {
+                        view: &surface_texture_view,
+                        resolve_target: None,
+                        depth_slice: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: wgpu::StoreOp::Store,
+                        },
                     },
                 })],
                 depth_stencil_attachment: None,
@@ -101,7 +253,7 @@ impl Renderer {
                 occlusion_query_set: None,
             });
 
-            self.egui_renderer.render(
+            egui_renderer.render(
                 &mut render_pass.forget_lifetime(),
                 &paint_jobs,
                 &screen_descriptor,
@@ -110,43 +262,66 @@ impl Renderer {
 
         self.gpu.queue.submit(std::iter::once(encoder.finish()));
         surface_texture.present();
+
+        Ok(())
     }
 }
 
+/// The wgpu instance, adapter, device and queue.
+///
+/// Created once, lazily, for the first egui window and then shared by all of them,
+/// so resources created on the `device` can be used from any window.
 #[derive(Debug)]
 pub struct Gpu {
-    pub surface: wgpu::Surface<'static>,
+    pub instance: wgpu::Instance,
+    pub adapter: wgpu::Adapter,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
-    pub surface_config: wgpu::SurfaceConfiguration,
-    pub surface_format: wgpu::TextureFormat,
 }
 
 impl Gpu {
-    // TODO: what is this used for?
-    // pub fn aspect_ratio(&self) -> f32 {
-    //     self.surface_config.width as f32 / self.surface_config.height.max(1) as f32
-    // }
-
-    pub fn resize(&mut self, width: u32, height: u32) {
-        self.surface_config.width = width;
-        self.surface_config.height = height;
-        self.surface.configure(&self.device, &self.surface_config);
+    /// Creates the GPU with the first adapter found along the fallback chain of `options`.
+    ///
+    /// `create_surface` is called with the instance of every attempt,
+    /// so the adapter is compatible with the returned surface.
+    pub async fn new_with_fallback(
+        options: &GpuOptions,
+        mut create_surface: impl FnMut(&wgpu::Instance) -> Result<Option<wgpu::Surface<'static>>, Error>,
+    ) -> Result<(Self, Option<wgpu::Surface<'static>>), Error> {
+        let mut chain = options.fallback_chain().into_iter().peekable();
+        while let Some(options) = chain.next() {
+            let instance = options.create_instance();
+            let result = match create_surface(&instance) {
+                Ok(surface) => Self::new_async(instance, surface.as_ref(), &options)
+                    .await
+                    .map(|gpu| (gpu, surface)),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok((gpu, surface)) => {
+                    debug!("Using the GPU adapter {:?}", gpu.adapter.get_info());
+                    return Ok((gpu, surface));
+                }
+                Err(e) if chain.peek().is_some() => warn!(
+                    "No GPU adapter found for {:?} (fallback: {}), trying the next one: {}",
+                    options.backends, options.force_fallback_adapter, e
+                ),
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!("the fallback chain is never empty")
     }
 
     pub async fn new_async(
-        window: impl Into<wgpu::SurfaceTarget<'static>>,
-        width: u32,
-        height: u32,
+        instance: wgpu::Instance,
+        compatible_surface: Option<&wgpu::Surface<'_>>,
+        options: &GpuOptions,
     ) -> Result<Self, Error> {
-        let instance = wgpu::Instance::default();
-        let surface = instance.create_surface(window)?;
-
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
+                power_preference: options.power_preference,
+                compatible_surface,
+                force_fallback_adapter: options.force_fallback_adapter,
             })
             .await?;
 
@@ -157,29 +332,297 @@ impl Gpu {
                     trace: wgpu::Trace::default(),
                     experimental_features: wgpu::ExperimentalFeatures::default(),
                     memory_hints: wgpu::MemoryHints::default(),
-                    required_features: wgpu::Features::default(),
-                    required_limits: wgpu::Limits::default().using_resolution(adapter.limits()),
+                    required_features: options.required_features,
+                    required_limits: options.required_limits.clone().unwrap_or_else(|| {
+                        wgpu::Limits::default().using_resolution(adapter.limits())
+                    }),
                 })
                 .await?
         };
 
-        let surface_capabilities = surface.get_capabilities(&adapter);
+        Ok(Self {
+            instance,
+            adapter,
+            device,
+            queue,
+        })
+    }
+}
 
-        let surface_format = surface_capabilities
-            .formats
-            .iter()
-            .copied()
-            .find(|f| {
-                !f.is_srgb() // egui wants a non-srgb surface texture
-                    && matches!(
-                        f,
-                        wgpu::TextureFormat::Rgba8Unorm
-                            | wgpu::TextureFormat::Rgba8UnormSrgb
-                            | wgpu::TextureFormat::Bgra8Unorm
-                            | wgpu::TextureFormat::Bgra8UnormSrgb
-                    )
-            })
-            .unwrap_or(surface_capabilities.formats[0]);
+/// Renders egui frames into an owned texture instead of a window surface,
+/// and reads the result back into CPU memory.
+///
+/// Useful to take screenshots of egui UIs, e.g. in CI machines without a GPU.
+pub struct OffscreenRenderer {
+    gpu: Gpu,
+    texture: wgpu::Texture,
+    egui_renderer: egui_wgpu::Renderer,
+}
+
+impl OffscreenRenderer {
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    /// Creates a renderer with its own GPU device.
+    ///
+    /// With `force_fallback_adapter`, a software adapter (e.g. lavapipe or WARP) is used.
+    /// Otherwise the default adapter is tried first, falling back to a software one.
+    pub async fn new(width: u32, height: u32, force_fallback_adapter: bool) -> Result<Self, Error> {
+        let options = GpuOptions {
+            force_fallback_adapter,
+            ..Default::default()
+        };
+        let (gpu, _) = Gpu::new_with_fallback(&options, |_| Ok(None)).await?;
+
+        let texture = Self::create_texture(&gpu.device, width, height);
+        let egui_renderer = egui_wgpu::Renderer::new(
+            &gpu.device,
+            Self::FORMAT,
+            egui_wgpu::RendererOptions::default(),
+        );
+
+        Ok(Self {
+            gpu,
+            texture,
+            egui_renderer,
+        })
+    }
+
+    /// Information about the adapter the frames are rendered with.
+    pub fn adapter_info(&self) -> wgpu::AdapterInfo {
+        self.gpu.adapter.get_info()
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.texture = Self::create_texture(&self.gpu.device, width, height);
+    }
+
+    /// Runs `ui_fn` for one frame filling the whole texture, and renders it.
+    pub fn render_ui(
+        &mut self,
+        context: &egui::Context,
+        ui_fn: impl FnMut(&egui::Context),
+    ) -> Result<egui::ColorImage, Error> {
+        let width = self.texture.width();
+        let height = self.texture.height();
+        let pixels_per_point = context.pixels_per_point();
+
+        let raw_input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::Vec2::new(width as f32, height as f32) / pixels_per_point,
+            )),
+            max_texture_side: Some(self.gpu.device.limits().max_texture_dimension_2d as usize),
+            ..Default::default()
+        };
+
+        let egui::FullOutput {
+            textures_delta,
+            shapes,
+            pixels_per_point,
+            ..
+        } = context.run(raw_input, ui_fn);
+
+        let paint_jobs = context.tessellate(shapes, pixels_per_point);
+
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [width, height],
+            pixels_per_point,
+        };
+
+        self.render_frame(screen_descriptor, paint_jobs, textures_delta)
+    }
+
+    /// Renders the paint jobs of a frame and returns the resulting (premultiplied) image.
+    ///
+    /// Use [`egui::ColorImage::as_raw`] to get the RGBA bytes.
+    pub fn render_frame(
+        &mut self,
+        screen_descriptor: egui_wgpu::ScreenDescriptor,
+        paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        textures_delta: egui::TexturesDelta,
+    ) -> Result<egui::ColorImage, Error> {
+        for (id, image_delta) in &textures_delta.set {
+            self.egui_renderer
+                .update_texture(&self.gpu.device, &self.gpu.queue, *id, image_delta);
+        }
+
+        for id in &textures_delta.free {
+            self.egui_renderer.free_texture(id);
+        }
+
+        let mut encoder = self
+            .gpu
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Offscreen Render Encoder"),
+            });
+
+        self.egui_renderer.update_buffers(
+            &self.gpu.device,
+            &self.gpu.queue,
+            &mut encoder,
+            &paint_jobs,
+            &screen_descriptor,
+        );
+
+        let texture_view = self
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        // need this block to preserve encoder ownership
+        {
+            let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Offscreen Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &texture_view,
+                    resolve_target: None,
+                    depth_slice: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            self.egui_renderer.render(
+                &mut render_pass.forget_lifetime(),
+                &paint_jobs,
+                &screen_descriptor,
+            );
+        }
+
+        // rows of a texture-to-buffer copy must be aligned
+        let width = self.texture.width();
+        let height = self.texture.height();
+        let unpadded_bytes_per_row = width * 4;
+        let padded_bytes_per_row = unpadded_bytes_per_row
+            .div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Offscreen Readback Buffer"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            self.texture.size(),
+        );
+
+        self.gpu.queue.submit(std::iter::once(encoder.finish()));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        buffer.map_async(wgpu::MapMode::Read, .., move |result| {
+            let _ = sender.send(result);
+        });
+        self.gpu.device.poll(wgpu::PollType::wait_indefinitely())?;
+        receiver.recv().map_err(|_| wgpu::BufferAsyncError)??;
+
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+        {
+            let mapped = buffer.get_mapped_range(..);
+            for row in mapped.chunks_exact(padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        buffer.unmap();
+
+        Ok(egui::ColorImage::from_rgba_premultiplied(
+            [width as usize, height as usize],
+            &pixels,
+        ))
+    }
+
+    fn create_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Texture"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        })
+    }
+}
+
+/// The surface of a single window, along with its configuration.
+#[derive(Debug)]
+pub struct WindowSurface {
+    pub surface: wgpu::Surface<'static>,
+    pub surface_config: wgpu::SurfaceConfiguration,
+    pub surface_format: wgpu::TextureFormat,
+}
+
+impl WindowSurface {
+    // TODO: what is this used for?
+    // pub fn aspect_ratio(&self) -> f32 {
+    //     self.surface_config.width as f32 / self.surface_config.height.max(1) as f32
+    // }
+
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.surface_config.width = width;
+        self.surface_config.height = height;
+        self.reconfigure(device);
+    }
+
+    pub fn reconfigure(&mut self, device: &wgpu::Device) {
+        self.surface.configure(device, &self.surface_config);
+    }
+
+    /// Configures the surface, with `required_format` if set.
+    pub fn new(
+        gpu: &Gpu,
+        surface: wgpu::Surface<'static>,
+        width: u32,
+        height: u32,
+        options: &RendererOptions,
+        required_format: Option<wgpu::TextureFormat>,
+    ) -> Result<Self, Error> {
+        if !gpu.adapter.is_surface_supported(&surface) {
+            return Err(Error::SurfaceUnsupported);
+        }
+
+        let surface_capabilities = surface.get_capabilities(&gpu.adapter);
+
+        let surface_format = match required_format {
+            // the windows of one egui context draw with the same pipeline
+            Some(format) if surface_capabilities.formats.contains(&format) => format,
+            Some(_) => return Err(Error::SurfaceUnsupported),
+            None => surface_capabilities
+                .formats
+                .iter()
+                .copied()
+                .find(|f| {
+                    !f.is_srgb() // egui wants a non-srgb surface texture
+                        && matches!(
+                            f,
+                            wgpu::TextureFormat::Rgba8Unorm
+                                | wgpu::TextureFormat::Rgba8UnormSrgb
+                                | wgpu::TextureFormat::Bgra8Unorm
+                                | wgpu::TextureFormat::Bgra8UnormSrgb
+                        )
+                })
+                .unwrap_or(surface_capabilities.formats[0]),
+        };
 
         let alpha_mode = surface_capabilities
             .alpha_modes
@@ -188,23 +631,33 @@ impl Gpu {
             .find(|m| *m != wgpu::CompositeAlphaMode::Opaque)
             .unwrap_or(surface_capabilities.alpha_modes[0]);
 
+        let present_mode = options
+            .present_mode
+            .filter(|mode| {
+                // the automatic modes always resolve to a supported one
+                matches!(mode, wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync)
+                    || surface_capabilities.present_modes.contains(mode)
+            })
+            .unwrap_or(surface_capabilities.present_modes[0]);
+
         let surface_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width,
             height,
-            present_mode: surface_capabilities.present_modes[0],
+            present_mode,
             alpha_mode,
             view_formats: vec![],
-            desired_maximum_frame_latency: 2,
+            desired_maximum_frame_latency: options.desired_maximum_frame_latency,
         };
 
-        surface.configure(&device, &surface_config);
+        // a minimized window can have no size, it's configured once resized
+        if width > 0 && height > 0 {
+            surface.configure(&gpu.device, &surface_config);
+        }
 
         Ok(Self {
             surface,
-            device,
-            queue,
             surface_config,
             surface_format,
         })
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use tauri_runtime::window::WindowId;
+use tauri_runtime::UserEvent;
+use tauri_runtime_wry::tao::event_loop::EventLoopProxy;
+use tauri_runtime_wry::{Message, WindowMessage};
+
+/// Requests window redraws at a later instant, e.g. for `ctx.request_repaint_after(..)`.
+///
+/// A single timer thread keeps the earliest pending deadline of every window,
+/// so idle windows don't cost any CPU while waiting.
+pub(crate) struct RedrawScheduler<T: UserEvent> {
+    proxy: EventLoopProxy<Message<T>>,
+    sender: mpsc::Sender<(WindowId, Instant)>,
+}
+
+impl<T: UserEvent> Clone for RedrawScheduler<T> {
+    fn clone(&self) -> Self {
+        Self {
+            proxy: self.proxy.clone(),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T: UserEvent> RedrawScheduler<T> {
+    pub fn new(proxy: EventLoopProxy<Message<T>>) -> Self {
+        let (sender, receiver) = mpsc::channel::<(WindowId, Instant)>();
+
+        let timer_proxy = proxy.clone();
+        std::thread::spawn(move || {
+            let mut deadlines: HashMap<WindowId, Instant> = HashMap::new();
+            loop {
+                let received = match deadlines.values().min() {
+                    Some(next) => {
+                        receiver.recv_timeout(next.saturating_duration_since(Instant::now()))
+                    }
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+
+                match received {
+                    Ok((win_id, at)) => {
+                        let deadline = deadlines.entry(win_id).or_insert(at);
+                        *deadline = (*deadline).min(at);
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // every sender is gone, nothing can be scheduled anymore
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                let now = Instant::now();
+                deadlines.retain(|win_id, at| {
+                    if *at > now {
+                        return true;
+                    }
+                    let _ = timer_proxy
+                        .send_event(Message::Window(*win_id, WindowMessage::RequestRedraw));
+                    false
+                });
+            }
+        });
+
+        Self { proxy, sender }
+    }
+
+    /// Requests a redraw of the window once `delay` has passed.
+    pub fn schedule(&self, win_id: WindowId, delay: Duration) {
+        if delay.is_zero() {
+            let _ = self
+                .proxy
+                .send_event(Message::Window(win_id, WindowMessage::RequestRedraw));
+            return;
+        }
+        // `Duration::MAX` means no repaint is needed at all
+        if let Some(at) = Instant::now().checked_add(delay) {
+            let _ = self.sender.send((win_id, at));
+        }
+    }
+}
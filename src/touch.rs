@@ -0,0 +1,166 @@
+use tauri_runtime_wry::tao::event::TouchPhase;
+
+/// Translates the phase of a touch, tao reports cancelled touches like egui.
+pub(crate) fn translate_touch_phase(phase: TouchPhase) -> egui::TouchPhase {
+    match phase {
+        TouchPhase::Started => egui::TouchPhase::Start,
+        TouchPhase::Moved => egui::TouchPhase::Move,
+        TouchPhase::Ended => egui::TouchPhase::End,
+        _ => egui::TouchPhase::Cancel,
+    }
+}
+
+/// The touch that also moves the pointer, so touches can click and hover widgets.
+///
+/// Only the first touch drives the pointer, like a mouse with the primary button held down,
+/// egui derives pinch zoom and rotation from all of them itself.
+#[derive(Debug, Default)]
+pub(crate) struct TouchPointer {
+    touch_id: Option<u64>,
+}
+
+impl TouchPointer {
+    /// The pointer events of the touch `id` at `pos`, in points.
+    ///
+    /// `pointer_pos` follows the touch driving the pointer and is cleared once it's lifted.
+    pub(crate) fn translate(
+        &mut self,
+        id: u64,
+        phase: egui::TouchPhase,
+        pos: egui::Pos2,
+        modifiers: egui::Modifiers,
+        pointer_pos: &mut Option<egui::Pos2>,
+    ) -> Vec<egui::Event> {
+        let is_pointer_touch = self.touch_id == Some(id);
+        match phase {
+            egui::TouchPhase::Start if self.touch_id.is_none() => {
+                self.touch_id = Some(id);
+                *pointer_pos = Some(pos);
+                vec![
+                    egui::Event::PointerMoved(pos),
+                    egui::Event::PointerButton {
+                        pos,
+                        button: egui::PointerButton::Primary,
+                        pressed: true,
+                        modifiers,
+                    },
+                ]
+            }
+            egui::TouchPhase::Move if is_pointer_touch => {
+                *pointer_pos = Some(pos);
+                vec![egui::Event::PointerMoved(pos)]
+            }
+            egui::TouchPhase::End if is_pointer_touch => {
+                self.touch_id = None;
+                *pointer_pos = None;
+                vec![
+                    egui::Event::PointerButton {
+                        pos,
+                        button: egui::PointerButton::Primary,
+                        pressed: false,
+                        modifiers,
+                    },
+                    // a lifted finger doesn't hover anything
+                    egui::Event::PointerGone,
+                ]
+            }
+            egui::TouchPhase::Cancel if is_pointer_touch => {
+                self.touch_id = None;
+                *pointer_pos = None;
+                vec![egui::Event::PointerGone]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use egui::TouchPhase::{Cancel, End, Move, Start};
+
+    fn press(pos: egui::Pos2, pressed: bool) -> egui::Event {
+        egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: egui::Modifiers::NONE,
+        }
+    }
+
+    /// A touch, with the pointer events it produces and the pointer position after it.
+    type Step = (u64, egui::TouchPhase, egui::Pos2, Vec<egui::Event>, Option<egui::Pos2>);
+
+    /// Runs a sequence of touches, checking the pointer events and position after each of them.
+    fn check(touches: &[Step]) {
+        let mut touch_pointer = TouchPointer::default();
+        let mut pointer_pos = None;
+        for (step, (id, phase, pos, events, expected_pos)) in touches.iter().enumerate() {
+            let translated =
+                touch_pointer.translate(*id, *phase, *pos, egui::Modifiers::NONE, &mut pointer_pos);
+            assert_eq!(&translated, events, "events of step {step}");
+            assert_eq!(pointer_pos, *expected_pos, "pointer position after step {step}");
+        }
+    }
+
+    #[test]
+    fn single_touch_clicks() {
+        let (a, b) = (egui::pos2(10.0, 20.0), egui::pos2(15.0, 25.0));
+        check(&[
+            (1, Start, a, vec![egui::Event::PointerMoved(a), press(a, true)], Some(a)),
+            (1, Move, b, vec![egui::Event::PointerMoved(b)], Some(b)),
+            (1, End, b, vec![press(b, false), egui::Event::PointerGone], None),
+        ]);
+    }
+
+    #[test]
+    fn cancelled_touch_releases_the_pointer() {
+        let a = egui::pos2(10.0, 20.0);
+        check(&[
+            (1, Start, a, vec![egui::Event::PointerMoved(a), press(a, true)], Some(a)),
+            (1, Cancel, a, vec![egui::Event::PointerGone], None),
+        ]);
+    }
+
+    #[test]
+    fn only_the_first_touch_drives_the_pointer() {
+        let (a, b, c) = (egui::pos2(10.0, 20.0), egui::pos2(50.0, 60.0), egui::pos2(55.0, 65.0));
+        check(&[
+            (1, Start, a, vec![egui::Event::PointerMoved(a), press(a, true)], Some(a)),
+            // a second finger, e.g. for pinch zoom
+            (2, Start, b, vec![], Some(a)),
+            (2, Move, c, vec![], Some(a)),
+            (2, End, c, vec![], Some(a)),
+            (1, End, a, vec![press(a, false), egui::Event::PointerGone], None),
+        ]);
+    }
+
+    #[test]
+    fn next_touch_drives_the_pointer_once_released() {
+        let (a, b, c) = (egui::pos2(10.0, 20.0), egui::pos2(50.0, 60.0), egui::pos2(55.0, 65.0));
+        check(&[
+            (1, Start, a, vec![egui::Event::PointerMoved(a), press(a, true)], Some(a)),
+            (2, Start, b, vec![], Some(a)),
+            (1, Cancel, a, vec![egui::Event::PointerGone], None),
+            // the remaining finger doesn't take over in the middle of a gesture
+            (2, Move, c, vec![], None),
+            (2, End, c, vec![], None),
+            (3, Start, c, vec![egui::Event::PointerMoved(c), press(c, true)], Some(c)),
+            (3, End, c, vec![press(c, false), egui::Event::PointerGone], None),
+        ]);
+    }
+
+    #[test]
+    fn phases() {
+        let cases = [
+            (TouchPhase::Started, Start),
+            (TouchPhase::Moved, Move),
+            (TouchPhase::Ended, End),
+            (TouchPhase::Cancelled, Cancel),
+        ];
+        for (phase, expected) in cases {
+            assert_eq!(translate_touch_phase(phase), expected, "{phase:?}");
+        }
+    }
+}
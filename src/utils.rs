@@ -25,4 +25,17 @@ pub(crate) fn get_label_from_tao_id<T: UserEvent>(
             .get(&id)
             .map(|ww| ww.label().to_string())
     })
-}
\ No newline at end of file
+}
+/// Gets the WindowId of a Tauri window from its label
+pub(crate) fn get_id_from_label<T: UserEvent>(
+    label: &str,
+    context: &EventLoopIterationContext<'_, T>,
+) -> Option<WindowId> {
+    context
+        .windows
+        .0
+        .borrow()
+        .iter()
+        .find(|(_, ww)| ww.label() == label)
+        .map(|(id, _)| *id)
+}
@@ -0,0 +1,303 @@
+use egui::{ViewportBuilder, ViewportCommand, ViewportId};
+use tauri::image::Image;
+use tauri::window::WindowBuilder;
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Position, Runtime, Size, Theme};
+use tauri_runtime::ResizeDirection;
+use tauri_runtime_wry::tao::window::{Icon, UserAttentionType};
+use tauri_runtime_wry::{UserAttentionTypeWrapper, WindowMessage};
+
+/// Translates a command egui sends to its viewport into the messages for the Tauri window.
+///
+/// Positions and sizes are in egui points, `pixels_per_point` converts them to physical pixels.
+/// Commands without a Tauri equivalent translate to nothing, e.g. `Transparent` or `IMERect`.
+/// `RequestCut`, `RequestCopy` and `RequestPaste` are egui input rather than window messages,
+/// so they are left to the caller as well.
+pub(crate) fn translate_viewport_command(
+    command: ViewportCommand,
+    pixels_per_point: f32,
+) -> Vec<WindowMessage> {
+    let to_position = |pos: egui::Pos2| {
+        Position::Physical(PhysicalPosition::new(
+            (pos.x * pixels_per_point).round() as i32,
+            (pos.y * pixels_per_point).round() as i32,
+        ))
+    };
+    let to_size = |size: egui::Vec2| {
+        Size::Physical(PhysicalSize::new(
+            (size.x * pixels_per_point).round() as u32,
+            (size.y * pixels_per_point).round() as u32,
+        ))
+    };
+
+    match command {
+        ViewportCommand::Close => vec![WindowMessage::Close],
+        ViewportCommand::Title(title) => vec![WindowMessage::SetTitle(title)],
+        ViewportCommand::Visible(true) => vec![WindowMessage::Show],
+        ViewportCommand::Visible(false) => vec![WindowMessage::Hide],
+        ViewportCommand::Focus => vec![WindowMessage::SetFocus],
+        ViewportCommand::StartDrag => vec![WindowMessage::DragWindow],
+        ViewportCommand::BeginResize(direction) => {
+            vec![WindowMessage::ResizeDragWindow(translate_resize_direction(direction))]
+        }
+
+        ViewportCommand::OuterPosition(pos) => vec![WindowMessage::SetPosition(to_position(pos))],
+        ViewportCommand::InnerSize(size) => vec![WindowMessage::SetSize(to_size(size))],
+        // egui uses infinity for no constraint
+        ViewportCommand::MinInnerSize(size) => {
+            vec![WindowMessage::SetMinSize(size.is_finite().then(|| to_size(size)))]
+        }
+        ViewportCommand::MaxInnerSize(size) => {
+            vec![WindowMessage::SetMaxSize(size.is_finite().then(|| to_size(size)))]
+        }
+
+        ViewportCommand::Resizable(resizable) => vec![WindowMessage::SetResizable(resizable)],
+        ViewportCommand::EnableButtons {
+            close,
+            minimized,
+            maximize,
+        } => vec![
+            WindowMessage::SetClosable(close),
+            WindowMessage::SetMinimizable(minimized),
+            WindowMessage::SetMaximizable(maximize),
+        ],
+        ViewportCommand::Minimized(true) => vec![WindowMessage::Minimize],
+        ViewportCommand::Minimized(false) => vec![WindowMessage::Unminimize],
+        ViewportCommand::Maximized(true) => vec![WindowMessage::Maximize],
+        ViewportCommand::Maximized(false) => vec![WindowMessage::Unmaximize],
+        ViewportCommand::Fullscreen(fullscreen) => vec![WindowMessage::SetFullscreen(fullscreen)],
+        ViewportCommand::Decorations(decorations) => {
+            vec![WindowMessage::SetDecorations(decorations)]
+        }
+        ViewportCommand::WindowLevel(level) => match level {
+            egui::WindowLevel::Normal => vec![
+                WindowMessage::SetAlwaysOnTop(false),
+                WindowMessage::SetAlwaysOnBottom(false),
+            ],
+            egui::WindowLevel::AlwaysOnTop => vec![WindowMessage::SetAlwaysOnTop(true)],
+            egui::WindowLevel::AlwaysOnBottom => vec![WindowMessage::SetAlwaysOnBottom(true)],
+        },
+        ViewportCommand::Icon(Some(icon)) => {
+            match Icon::from_rgba(icon.rgba.clone(), icon.width, icon.height) {
+                Ok(icon) => vec![WindowMessage::SetIcon(icon)],
+                Err(e) => {
+                    warn!("Invalid window icon: {}", e);
+                    vec![]
+                }
+            }
+        }
+        ViewportCommand::RequestUserAttention(attention) => {
+            let attention = match attention {
+                egui::UserAttentionType::Critical => Some(UserAttentionType::Critical),
+                egui::UserAttentionType::Informational => Some(UserAttentionType::Informational),
+                egui::UserAttentionType::Reset => None,
+            };
+            vec![WindowMessage::RequestUserAttention(
+                attention.map(UserAttentionTypeWrapper),
+            )]
+        }
+        ViewportCommand::SetTheme(theme) => {
+            let theme = match theme {
+                egui::SystemTheme::SystemDefault => None,
+                egui::SystemTheme::Light => Some(Theme::Light),
+                egui::SystemTheme::Dark => Some(Theme::Dark),
+            };
+            vec![WindowMessage::SetTheme(theme)]
+        }
+        ViewportCommand::ContentProtected(protected) => {
+            vec![WindowMessage::SetContentProtected(protected)]
+        }
+
+        ViewportCommand::CursorPosition(pos) => {
+            vec![WindowMessage::SetCursorPosition(to_position(pos))]
+        }
+        ViewportCommand::CursorGrab(grab) => {
+            vec![WindowMessage::SetCursorGrab(grab != egui::CursorGrab::None)]
+        }
+        ViewportCommand::CursorVisible(visible) => vec![WindowMessage::SetCursorVisible(visible)],
+        ViewportCommand::MousePassthrough(passthrough) => {
+            vec![WindowMessage::SetIgnoreCursorEvents(passthrough)]
+        }
+
+        command => {
+            debug!("Unsupported viewport command: {:?}", command);
+            vec![]
+        }
+    }
+}
+
+fn translate_resize_direction(direction: egui::ResizeDirection) -> ResizeDirection {
+    match direction {
+        egui::ResizeDirection::North => ResizeDirection::North,
+        egui::ResizeDirection::South => ResizeDirection::South,
+        egui::ResizeDirection::East => ResizeDirection::East,
+        egui::ResizeDirection::West => ResizeDirection::West,
+        egui::ResizeDirection::NorthEast => ResizeDirection::NorthEast,
+        egui::ResizeDirection::SouthEast => ResizeDirection::SouthEast,
+        egui::ResizeDirection::NorthWest => ResizeDirection::NorthWest,
+        egui::ResizeDirection::SouthWest => ResizeDirection::SouthWest,
+    }
+}
+
+/// The label of the Tauri window opened for an egui viewport, from the window it was opened by.
+pub(crate) fn viewport_label(parent_label: &str, id: ViewportId) -> String {
+    format!("{}-viewport-{:016x}", parent_label, id.0.value())
+}
+
+/// Builds the Tauri window for an egui viewport, as described by its [`ViewportBuilder`].
+///
+/// Positions and sizes are in egui points, `zoom_factor` converts them to logical pixels.
+/// Settings without a Tauri equivalent are left out, e.g. `app_id` or `mouse_passthrough`.
+pub(crate) fn window_builder<'a, R: Runtime, M: Manager<R>>(
+    manager: &'a M,
+    label: &str,
+    builder: &ViewportBuilder,
+    zoom_factor: f32,
+) -> WindowBuilder<'a, R, M> {
+    let to_logical = |v: egui::Vec2| ((v.x * zoom_factor) as f64, (v.y * zoom_factor) as f64);
+
+    let mut window = WindowBuilder::new(manager, label);
+    if let Some(title) = &builder.title {
+        window = window.title(title);
+    }
+    if let Some(pos) = builder.position {
+        let (x, y) = to_logical(pos.to_vec2());
+        window = window.position(x, y);
+    }
+    if let Some(size) = builder.inner_size {
+        let (width, height) = to_logical(size);
+        window = window.inner_size(width, height);
+    }
+    // egui uses infinity for no constraint
+    if let Some(size) = builder.min_inner_size.filter(|size| size.is_finite()) {
+        let (width, height) = to_logical(size);
+        window = window.min_inner_size(width, height);
+    }
+    if let Some(size) = builder.max_inner_size.filter(|size| size.is_finite()) {
+        let (width, height) = to_logical(size);
+        window = window.max_inner_size(width, height);
+    }
+
+    if let Some(resizable) = builder.resizable {
+        window = window.resizable(resizable);
+    }
+    if let Some(decorations) = builder.decorations {
+        window = window.decorations(decorations);
+    }
+    if let Some(transparent) = builder.transparent {
+        window = window.transparent(transparent);
+    }
+    if let Some(fullscreen) = builder.fullscreen {
+        window = window.fullscreen(fullscreen);
+    }
+    if let Some(maximized) = builder.maximized {
+        window = window.maximized(maximized);
+    }
+    if let Some(visible) = builder.visible {
+        window = window.visible(visible);
+    }
+    if let Some(active) = builder.active {
+        window = window.focused(active);
+    }
+    if let Some(taskbar) = builder.taskbar {
+        window = window.skip_taskbar(!taskbar);
+    }
+    if let Some(close) = builder.close_button {
+        window = window.closable(close);
+    }
+    if let Some(minimize) = builder.minimize_button {
+        window = window.minimizable(minimize);
+    }
+    if let Some(maximize) = builder.maximize_button {
+        window = window.maximizable(maximize);
+    }
+    match builder.window_level {
+        Some(egui::WindowLevel::AlwaysOnTop) => window = window.always_on_top(true),
+        Some(egui::WindowLevel::AlwaysOnBottom) => window = window.always_on_bottom(true),
+        Some(egui::WindowLevel::Normal) | None => {}
+    }
+    if let Some(icon) = &builder.icon {
+        let image = Image::new_owned(icon.rgba.clone(), icon.width, icon.height);
+        window = match window.icon(image) {
+            Ok(window) => window,
+            Err(e) => {
+                warn!("Invalid viewport icon: {}", e);
+                // `icon` consumed the builder, start over without it
+                return window_builder(
+                    manager,
+                    label,
+                    &ViewportBuilder { icon: None, ..builder.clone() },
+                    zoom_factor,
+                );
+            }
+        };
+    }
+
+    window
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(command: ViewportCommand) -> Vec<WindowMessage> {
+        translate_viewport_command(command, 1.5)
+    }
+
+    fn physical_size(width: u32, height: u32) -> Size {
+        Size::Physical(PhysicalSize::new(width, height))
+    }
+
+    #[test]
+    fn close() {
+        assert!(matches!(translate(ViewportCommand::Close)[..], [WindowMessage::Close]));
+    }
+
+    #[test]
+    fn points_are_scaled_to_physical_pixels() {
+        let messages = translate(ViewportCommand::InnerSize(egui::vec2(100.0, 50.5)));
+        assert!(matches!(
+            &messages[..],
+            [WindowMessage::SetSize(size)] if *size == physical_size(150, 76)
+        ));
+
+        let messages = translate(ViewportCommand::OuterPosition(egui::pos2(-20.0, 33.0)));
+        assert!(matches!(
+            &messages[..],
+            [WindowMessage::SetPosition(pos)]
+                if *pos == Position::Physical(PhysicalPosition::new(-30, 50))
+        ));
+
+        let messages = translate(ViewportCommand::MinInnerSize(egui::vec2(10.0, 20.0)));
+        assert!(matches!(
+            &messages[..],
+            [WindowMessage::SetMinSize(Some(size))] if *size == physical_size(15, 30)
+        ));
+    }
+
+    #[test]
+    fn infinite_size_constraints_are_removed() {
+        let messages = translate(ViewportCommand::MaxInnerSize(egui::Vec2::INFINITY));
+        assert!(matches!(messages[..], [WindowMessage::SetMaxSize(None)]));
+    }
+
+    #[test]
+    fn unsupported_commands_translate_to_nothing() {
+        let commands = [
+            ViewportCommand::Transparent(true),
+            ViewportCommand::Icon(None),
+            ViewportCommand::IMEAllowed(true),
+            ViewportCommand::IMERect(egui::Rect::ZERO),
+            ViewportCommand::IMEPurpose(egui::IMEPurpose::Normal),
+            ViewportCommand::Screenshot(egui::UserData::default()),
+            ViewportCommand::CancelClose,
+            // egui input, left to the caller
+            ViewportCommand::RequestCut,
+            ViewportCommand::RequestCopy,
+            ViewportCommand::RequestPaste,
+        ];
+        for command in commands {
+            let description = format!("{command:?}");
+            assert!(translate(command).is_empty(), "{description}");
+        }
+    }
+}